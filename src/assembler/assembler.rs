@@ -2,21 +2,39 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
+use crate::assembler::debug_map::{DebugMap, DebugMapEntry};
 use crate::assembler::encoding::{
     encode_instruction, ensure_range, parse_numeric_literal, parse_value,
 };
-use crate::assembler::error::AssemblerError;
+use crate::assembler::error::{AssemblerError, ColumnSpan, SourcePosition};
+use crate::assembler::expression::evaluate_expression;
+use crate::assembler::preprocessor::{preprocess, preprocess_with_resolver};
+use crate::assembler::resolver::{FilesystemResolver, SourceResolver};
 
 #[derive(Debug, Clone)]
 struct Statement {
-    line_no: usize,
+    position: SourcePosition,
     kind: StatementKind,
     operation: String,
     arguments: Vec<String>,
+    /// The label/comment-stripped line text, kept around so a later encoding
+    /// failure can render a snippet pointing back at it.
+    line_text: String,
+    /// Span of the whole argument list within `line_text` (`None` when the
+    /// statement takes no arguments), used as the diagnostic's underline.
+    arg_span: Option<ColumnSpan>,
+    /// Byte size this statement was estimated to occupy during `parse_source`.
+    /// `encode_statements` keeps emitting exactly this many bytes even when
+    /// encoding a statement fails, so addresses already baked into `labels`
+    /// stay consistent with the bytes actually written.
+    size: usize,
 }
 
+/// What kind of statement occupies a `Statement`'s address range. Exposed
+/// publicly (via `DebugMapEntry::statement_kind`) so a debug-map consumer can
+/// tell a data directive apart from an instruction without re-parsing the ROM.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum StatementKind {
+pub enum StatementKind {
     DirectiveOrg,
     DirectiveDb,
     DirectiveDw,
@@ -24,39 +42,183 @@ enum StatementKind {
 }
 
 pub fn assemble_file(path: impl AsRef<Path>, origin: usize) -> Result<Vec<u8>, AssemblerError> {
-    let source =
-        fs::read_to_string(path).map_err(|error| AssemblerError::new(error.to_string(), None))?;
-    assemble_text(&source, origin)
+    assemble_file_with_diagnostics(path, origin).map_err(first_error)
 }
 
 pub fn assemble_text(source: &str, origin: usize) -> Result<Vec<u8>, AssemblerError> {
-    let (statements, labels) = parse_source(source, origin)?;
-    encode_statements(&statements, &labels, origin)
+    assemble_text_with_diagnostics(source, origin).map_err(first_error)
 }
 
-fn parse_source(
+/// Assembles `source`, returning every independent diagnostic collected
+/// during the pass instead of stopping at the first one. `parse_source` and
+/// `encode_statements` keep going after a recoverable problem -- a malformed
+/// statement is skipped (or, once its size is known, encoded as a zero-filled
+/// placeholder) rather than aborting the whole pass -- so editor tooling can
+/// report every problem in a file at once. Use `format_diagnostics` to render
+/// the result.
+pub fn assemble_text_with_diagnostics(
+    source: &str,
+    origin: usize,
+) -> Result<Vec<u8>, Vec<AssemblerError>> {
+    assemble_preprocessed(source, "<source>", None, origin)
+}
+
+pub fn assemble_file_with_diagnostics(
+    path: impl AsRef<Path>,
+    origin: usize,
+) -> Result<Vec<u8>, Vec<AssemblerError>> {
+    let path = path.as_ref();
+    let source = fs::read_to_string(path)
+        .map_err(|error| vec![AssemblerError::new(error.to_string(), None)])?;
+    let source_name = path.display().to_string();
+    let base_dir = path.parent().map(Path::to_path_buf);
+    assemble_preprocessed(&source, &source_name, base_dir.as_deref(), origin)
+}
+
+/// Assembles `source`, also returning a `DebugMap` that maps every emitted
+/// byte address back to the source line and statement kind that produced it
+/// -- the basis for a step-debugger's "currently executing line N" display
+/// (see `DebugController::current_line`).
+pub fn assemble_text_with_debug(
+    source: &str,
+    origin: usize,
+) -> Result<(Vec<u8>, DebugMap), AssemblerError> {
+    assemble_preprocessed_with_debug(source, "<source>", None, origin).map_err(first_error)
+}
+
+pub fn assemble_file_with_debug(
+    path: impl AsRef<Path>,
+    origin: usize,
+) -> Result<(Vec<u8>, DebugMap), AssemblerError> {
+    assemble_file_with_debug_diagnostics(path, origin).map_err(first_error)
+}
+
+fn assemble_file_with_debug_diagnostics(
+    path: impl AsRef<Path>,
+    origin: usize,
+) -> Result<(Vec<u8>, DebugMap), Vec<AssemblerError>> {
+    let path = path.as_ref();
+    let source = fs::read_to_string(path)
+        .map_err(|error| vec![AssemblerError::new(error.to_string(), None)])?;
+    let source_name = path.display().to_string();
+    let base_dir = path.parent().map(Path::to_path_buf);
+    assemble_preprocessed_with_debug(&source, &source_name, base_dir.as_deref(), origin)
+}
+
+/// Assembles `source`, resolving `#include`/`INCLUDE` targets through
+/// `resolver` instead of the filesystem, e.g. an `InMemoryResolver` holding a
+/// shared library of sprite/math routines, so a multi-file program can be
+/// assembled (and tested) without touching disk.
+pub fn assemble_text_with_resolver(
+    source: &str,
+    origin: usize,
+    resolver: Box<dyn SourceResolver>,
+) -> Result<Vec<u8>, AssemblerError> {
+    assemble_preprocessed_with_resolver(source, "<source>", None, resolver, origin)
+        .map_err(first_error)
+}
+
+fn first_error(errors: Vec<AssemblerError>) -> AssemblerError {
+    errors
+        .into_iter()
+        .next()
+        .expect("Err is never returned with an empty diagnostics list")
+}
+
+fn assemble_preprocessed(
     source: &str,
+    source_name: &str,
+    base_dir: Option<&Path>,
     origin: usize,
-) -> Result<(Vec<Statement>, HashMap<String, usize>), AssemblerError> {
+) -> Result<Vec<u8>, Vec<AssemblerError>> {
+    assemble_preprocessed_with_resolver(
+        source,
+        source_name,
+        base_dir,
+        Box::new(FilesystemResolver),
+        origin,
+    )
+}
+
+fn assemble_preprocessed_with_resolver(
+    source: &str,
+    source_name: &str,
+    base_dir: Option<&Path>,
+    resolver: Box<dyn SourceResolver>,
+    origin: usize,
+) -> Result<Vec<u8>, Vec<AssemblerError>> {
+    let (lines, _preprocessor) = preprocess_with_resolver(source, source_name, base_dir, resolver)
+        .map_err(|error| vec![error])?;
+    let (statements, labels) = parse_source(&lines, origin)?;
+    encode_statements(&statements, &labels, origin, None)
+}
+
+fn assemble_preprocessed_with_debug(
+    source: &str,
+    source_name: &str,
+    base_dir: Option<&Path>,
+    origin: usize,
+) -> Result<(Vec<u8>, DebugMap), Vec<AssemblerError>> {
+    let (lines, _preprocessor) =
+        preprocess(source, source_name, base_dir).map_err(|error| vec![error])?;
+    let (statements, labels) = parse_source(&lines, origin)?;
+    let mut debug_map = DebugMap::new();
+    let bytes = encode_statements(&statements, &labels, origin, Some(&mut debug_map))?;
+    Ok((bytes, debug_map))
+}
+
+fn parse_source(
+    lines: &[(SourcePosition, String)],
+    origin: usize,
+) -> Result<(Vec<Statement>, HashMap<String, usize>), Vec<AssemblerError>> {
     let mut statements = Vec::new();
     let mut labels = HashMap::new();
+    let mut label_positions: HashMap<String, SourcePosition> = HashMap::new();
+    let mut equ_positions: HashMap<String, SourcePosition> = HashMap::new();
+    let mut pending_equs: Vec<(String, String, SourcePosition)> = Vec::new();
     let mut program_counter = origin;
+    let mut errors: Vec<AssemblerError> = Vec::new();
 
-    for (line_no, raw_line) in source.lines().enumerate() {
-        let line_no = line_no + 1;
+    'lines: for (position, raw_line) in lines {
+        let position = *position;
         let content = strip_comments(raw_line).trim().to_owned();
         if content.is_empty() {
             continue;
         }
 
-        let (line_labels, remainder) = split_labels(&content, line_no)?;
+        let (line_labels, remainder) = match split_labels(&content, position) {
+            Ok(parsed) => parsed,
+            Err(error) => {
+                errors.push(error);
+                continue;
+            }
+        };
+
         for label in line_labels {
-            if labels.contains_key(&label) {
-                return Err(AssemblerError::new(
-                    format!("duplicate label '{label}'"),
-                    Some(line_no),
+            if let Some(first_position) = label_positions.get(&label) {
+                errors.push(AssemblerError::new(
+                    format!(
+                        "duplicate label '{label}' (first defined at file #{}, line {}; redefined at file #{}, line {})",
+                        first_position.file_index,
+                        first_position.line,
+                        position.file_index,
+                        position.line
+                    ),
+                    Some(position),
+                ));
+                continue 'lines;
+            }
+            if let Some(first_position) = equ_positions.get(&label) {
+                errors.push(AssemblerError::new(
+                    format!(
+                        "duplicate symbol '{label}': already defined as an EQU constant at file #{}, line {}",
+                        first_position.file_index, first_position.line
+                    ),
+                    Some(position),
                 ));
+                continue 'lines;
             }
+            label_positions.insert(label.clone(), position);
             labels.insert(label, program_counter);
         }
 
@@ -64,123 +226,318 @@ fn parse_source(
             continue;
         }
 
-        let (operation, arguments) = split_operation_and_arguments(&remainder);
+        if let Some((name, expr)) = split_equ(&remainder) {
+            if let Err(error) = validate_label(&name, position) {
+                errors.push(error);
+                continue;
+            }
+            if let Some(first_position) = label_positions.get(&name) {
+                errors.push(AssemblerError::new(
+                    format!(
+                        "duplicate symbol '{name}': already defined as a label at file #{}, line {}",
+                        first_position.file_index, first_position.line
+                    ),
+                    Some(position),
+                ));
+                continue;
+            }
+            if let Some(first_position) = equ_positions.get(&name) {
+                errors.push(AssemblerError::new(
+                    format!(
+                        "duplicate EQU constant '{name}' (first defined at file #{}, line {})",
+                        first_position.file_index, first_position.line
+                    ),
+                    Some(position),
+                ));
+                continue;
+            }
+            equ_positions.insert(name.clone(), position);
+            pending_equs.push((name, expr, position));
+            continue;
+        }
+
+        let (operation, spanned_arguments) = split_operation_and_arguments(&remainder);
         let normalized = normalize_operation(&operation);
         let kind = classify_operation(&normalized);
+        let arguments: Vec<String> = spanned_arguments
+            .iter()
+            .map(|(value, _)| value.clone())
+            .collect();
+        let arg_span = combine_spans(spanned_arguments.iter().map(|(_, span)| *span));
 
-        statements.push(Statement {
-            line_no,
-            kind,
-            operation: normalized.clone(),
-            arguments: arguments.clone(),
-        });
-
-        match kind {
+        let size = match kind {
             StatementKind::DirectiveOrg => {
                 if arguments.len() != 1 {
-                    return Err(AssemblerError::new(
+                    errors.push(AssemblerError::new(
                         "ORG expects exactly one argument",
-                        Some(line_no),
+                        Some(position),
                     ));
+                    continue;
                 }
 
-                let target = parse_numeric_literal(&arguments[0], line_no)? as usize;
+                let target = match parse_numeric_literal(&arguments[0], position) {
+                    Ok(target) => target as usize,
+                    Err(error) => {
+                        errors.push(error.with_snippet(remainder.clone(), arg_span.unwrap()));
+                        continue;
+                    }
+                };
                 if target < origin {
-                    return Err(AssemblerError::new(
-                        format!("ORG target 0x{target:03X} cannot be below origin 0x{origin:03X}"),
-                        Some(line_no),
-                    ));
+                    errors.push(
+                        AssemblerError::new(
+                            format!(
+                                "ORG target 0x{target:03X} cannot be below origin 0x{origin:03X}"
+                            ),
+                            Some(position),
+                        )
+                        .with_snippet(remainder.clone(), arg_span.unwrap()),
+                    );
+                    continue;
                 }
                 if target < program_counter {
-                    return Err(AssemblerError::new(
-                        format!(
-                            "ORG target 0x{target:03X} cannot move backwards from 0x{program_counter:03X}"
-                        ),
-                        Some(line_no),
-                    ));
+                    errors.push(
+                        AssemblerError::new(
+                            format!(
+                                "ORG target 0x{target:03X} cannot move backwards from 0x{program_counter:03X}"
+                            ),
+                            Some(position),
+                        )
+                        .with_snippet(remainder.clone(), arg_span.unwrap()),
+                    );
+                    continue;
                 }
                 program_counter = target;
+                0
             }
             StatementKind::DirectiveDb => {
                 if arguments.is_empty() {
-                    return Err(AssemblerError::new(
+                    errors.push(AssemblerError::new(
                         "DB expects at least one argument",
-                        Some(line_no),
+                        Some(position),
                     ));
+                    continue;
+                }
+                match count_db_bytes(&arguments, position) {
+                    Ok(count) => count,
+                    Err(error) => {
+                        errors.push(error);
+                        continue;
+                    }
                 }
-                program_counter += count_db_bytes(&arguments, line_no)?;
             }
             StatementKind::DirectiveDw => {
                 if arguments.is_empty() {
-                    return Err(AssemblerError::new(
+                    errors.push(AssemblerError::new(
                         "DW expects at least one argument",
-                        Some(line_no),
+                        Some(position),
                     ));
+                    continue;
                 }
-                program_counter += 2 * arguments.len();
+                2 * arguments.len()
             }
-            StatementKind::Instruction => {
-                program_counter += 2;
+            StatementKind::Instruction => 2,
+        };
+        program_counter += size;
+
+        statements.push(Statement {
+            position,
+            kind,
+            operation: normalized,
+            arguments,
+            line_text: remainder,
+            arg_span,
+            size,
+        });
+    }
+
+    errors.extend(resolve_equ_constants(pending_equs, &mut labels));
+
+    if errors.is_empty() {
+        Ok((statements, labels))
+    } else {
+        Err(errors)
+    }
+}
+
+/// Sums the spans of a statement's arguments into one span covering the
+/// whole argument list, e.g. for underlining `V0, 5` in `ADD V0, 5`.
+fn combine_spans(spans: impl Iterator<Item = ColumnSpan>) -> Option<ColumnSpan> {
+    spans.reduce(|acc, span| ColumnSpan::new(acc.start.min(span.start), acc.end.max(span.end)))
+}
+
+/// Evaluates each `EQU` constant's expression and inserts it into `labels`,
+/// so it resolves exactly like a label from here on. Deferred until every
+/// label's address is known (all `EQU`s are collected before this runs), and
+/// iterated to a fixpoint so one `EQU` can reference another defined later
+/// in the file.
+fn resolve_equ_constants(
+    mut pending: Vec<(String, String, SourcePosition)>,
+    labels: &mut HashMap<String, usize>,
+) -> Vec<AssemblerError> {
+    while !pending.is_empty() {
+        let mut unresolved = Vec::new();
+        let mut progressed = false;
+
+        for (name, expr, position) in pending {
+            match evaluate_expression(&expr, labels, position) {
+                Ok(value) => {
+                    labels.insert(name, value as usize);
+                    progressed = true;
+                }
+                Err(error) => unresolved.push((name, expr, position, error)),
             }
         }
+
+        if !progressed {
+            return unresolved
+                .into_iter()
+                .map(|(name, _, position, error)| {
+                    AssemblerError::new(
+                        format!("could not resolve EQU '{name}': {}", error.message),
+                        Some(position),
+                    )
+                })
+                .collect();
+        }
+
+        pending = unresolved
+            .into_iter()
+            .map(|(name, expr, position, _)| (name, expr, position))
+            .collect();
     }
 
-    Ok((statements, labels))
+    Vec::new()
+}
+
+/// If `content` is `NAME EQU <expr>`, returns `(NAME, expr)`.
+fn split_equ(content: &str) -> Option<(String, String)> {
+    let mut parts = content.splitn(2, char::is_whitespace);
+    let name = parts.next()?;
+    let rest = parts.next()?.trim_start();
+    let keyword = rest.split_whitespace().next()?;
+    if !keyword.eq_ignore_ascii_case("EQU") {
+        return None;
+    }
+    let expr = rest[keyword.len()..].trim();
+    if expr.is_empty() {
+        return None;
+    }
+    Some((name.to_owned(), expr.to_owned()))
 }
 
 fn encode_statements(
     statements: &[Statement],
     labels: &HashMap<String, usize>,
     origin: usize,
-) -> Result<Vec<u8>, AssemblerError> {
+    mut debug_map: Option<&mut DebugMap>,
+) -> Result<Vec<u8>, Vec<AssemblerError>> {
     let mut output = Vec::new();
     let mut current_address = origin;
+    let mut errors: Vec<AssemblerError> = Vec::new();
 
     for statement in statements {
+        let enrich = |error: AssemblerError| match statement.arg_span {
+            Some(span) => error.with_snippet(statement.line_text.clone(), span),
+            None => error,
+        };
+        let address_start = current_address;
+
         match statement.kind {
             StatementKind::DirectiveOrg => {
-                let target =
-                    parse_value(&statement.arguments[0], labels, statement.line_no)? as usize;
+                let target = match parse_value(&statement.arguments[0], labels, statement.position)
+                {
+                    Ok(target) => target as usize,
+                    Err(error) => {
+                        errors.push(enrich(error));
+                        continue;
+                    }
+                };
                 if target < current_address {
-                    return Err(AssemblerError::new(
+                    errors.push(enrich(AssemblerError::new(
                         format!(
                             "ORG target 0x{target:03X} cannot move backwards from 0x{current_address:03X}"
                         ),
-                        Some(statement.line_no),
-                    ));
+                        Some(statement.position),
+                    )));
+                    continue;
                 }
                 output.extend(vec![0; target - current_address]);
                 current_address = target;
             }
             StatementKind::DirectiveDb => {
-                let db_values = encode_db_values(&statement.arguments, labels, statement.line_no)?;
-                current_address += db_values.len();
-                output.extend(db_values);
+                match encode_db_values(&statement.arguments, labels, statement.position) {
+                    Ok(db_values) => {
+                        current_address += db_values.len();
+                        output.extend(db_values);
+                    }
+                    Err(error) => {
+                        errors.push(enrich(error));
+                        output.extend(vec![0; statement.size]);
+                        current_address += statement.size;
+                    }
+                }
             }
             StatementKind::DirectiveDw => {
-                for argument in &statement.arguments {
-                    let word = parse_value(argument, labels, statement.line_no)?;
-                    ensure_range(word, 0, 0xFFFF, "word", statement.line_no)?;
-                    output.push(((word >> 8) & 0xFF) as u8);
-                    output.push((word & 0xFF) as u8);
-                    current_address += 2;
+                let words: Result<Vec<i32>, AssemblerError> = statement
+                    .arguments
+                    .iter()
+                    .map(|argument| {
+                        let word = parse_value(argument, labels, statement.position)?;
+                        ensure_range(word, 0, 0xFFFF, "word", statement.position)?;
+                        Ok(word)
+                    })
+                    .collect();
+
+                match words {
+                    Ok(words) => {
+                        for word in words {
+                            output.push(((word >> 8) & 0xFF) as u8);
+                            output.push((word & 0xFF) as u8);
+                            current_address += 2;
+                        }
+                    }
+                    Err(error) => {
+                        errors.push(enrich(error));
+                        output.extend(vec![0; statement.size]);
+                        current_address += statement.size;
+                    }
                 }
             }
             StatementKind::Instruction => {
-                let opcode = encode_instruction(
+                match encode_instruction(
                     &statement.operation,
                     &statement.arguments,
                     labels,
-                    statement.line_no,
-                )?;
-                output.push(((opcode >> 8) & 0xFF) as u8);
-                output.push((opcode & 0xFF) as u8);
-                current_address += 2;
+                    statement.position,
+                ) {
+                    Ok(opcode) => {
+                        output.push(((opcode >> 8) & 0xFF) as u8);
+                        output.push((opcode & 0xFF) as u8);
+                    }
+                    Err(error) => {
+                        errors.push(enrich(error));
+                        output.extend(vec![0; statement.size]);
+                    }
+                }
+                current_address += statement.size;
             }
         }
+
+        if let Some(debug_map) = debug_map.as_deref_mut() {
+            debug_map.push(DebugMapEntry {
+                address_start,
+                address_end: current_address,
+                position: statement.position,
+                statement_kind: statement.kind,
+            });
+        }
     }
 
-    Ok(output)
+    if errors.is_empty() {
+        Ok(output)
+    } else {
+        Err(errors)
+    }
 }
 
 fn strip_comments(line: &str) -> String {
@@ -201,7 +558,10 @@ fn strip_comments(line: &str) -> String {
     line.to_owned()
 }
 
-fn split_labels(content: &str, line_no: usize) -> Result<(Vec<String>, String), AssemblerError> {
+fn split_labels(
+    content: &str,
+    position: SourcePosition,
+) -> Result<(Vec<String>, String), AssemblerError> {
     let mut labels = Vec::new();
     let mut remainder = content.trim().to_owned();
 
@@ -217,7 +577,7 @@ fn split_labels(content: &str, line_no: usize) -> Result<(Vec<String>, String),
             return Ok((labels, remainder.trim().to_owned()));
         }
 
-        validate_label(before, line_no)?;
+        validate_label(before, position)?;
         labels.push(before.to_owned());
         remainder = after.to_owned();
 
@@ -227,76 +587,98 @@ fn split_labels(content: &str, line_no: usize) -> Result<(Vec<String>, String),
     }
 }
 
-fn validate_label(label: &str, line_no: usize) -> Result<(), AssemblerError> {
+fn validate_label(label: &str, position: SourcePosition) -> Result<(), AssemblerError> {
     let mut chars = label.chars();
     let first = chars
         .next()
-        .ok_or_else(|| AssemblerError::new(format!("invalid label '{label}'"), Some(line_no)))?;
+        .ok_or_else(|| AssemblerError::new(format!("invalid label '{label}'"), Some(position)))?;
 
     if !first.is_ascii_alphabetic() && first != '_' {
         return Err(AssemblerError::new(
             format!("invalid label '{label}'"),
-            Some(line_no),
+            Some(position),
         ));
     }
 
     if chars.any(|ch| !ch.is_ascii_alphanumeric() && ch != '_') {
         return Err(AssemblerError::new(
             format!("invalid label '{label}'"),
-            Some(line_no),
+            Some(position),
         ));
     }
 
     Ok(())
 }
 
-fn split_operation_and_arguments(text: &str) -> (String, Vec<String>) {
+/// Splits a label/comment-stripped line into its operation mnemonic and
+/// comma-separated arguments, each tagged with its byte-offset span within
+/// `text`. `parse_source` merges these spans into one span over the whole
+/// argument list, so an encoding error underlines every argument rather than
+/// only the offending one.
+fn split_operation_and_arguments(text: &str) -> (String, Vec<(String, ColumnSpan)>) {
     let mut parts = text.splitn(2, char::is_whitespace);
     let operation = parts.next().unwrap_or("").to_owned();
-    let arguments = parts.next().map(split_arguments).unwrap_or_default();
+    let arguments = match parts.next() {
+        Some(rest) => {
+            let base_offset = text.len() - rest.len();
+            split_arguments(rest, base_offset)
+        }
+        None => Vec::new(),
+    };
     (operation, arguments)
 }
 
-fn split_arguments(text: &str) -> Vec<String> {
+fn split_arguments(text: &str, base_offset: usize) -> Vec<(String, ColumnSpan)> {
     if text.is_empty() {
         return Vec::new();
     }
 
     let mut args = Vec::new();
-    let mut token = String::new();
+    let mut token_start = 0usize;
     let mut in_quote: Option<char> = None;
 
-    for ch in text.chars() {
+    for (index, ch) in text.char_indices() {
         if ch == '\'' || ch == '"' {
             if in_quote.is_none() {
                 in_quote = Some(ch);
             } else if in_quote == Some(ch) {
                 in_quote = None;
             }
-            token.push(ch);
             continue;
         }
 
         if ch == ',' && in_quote.is_none() {
-            let value = token.trim();
-            if !value.is_empty() {
-                args.push(value.to_owned());
-            }
-            token.clear();
-            continue;
+            push_trimmed_argument(&mut args, text, token_start, index, base_offset);
+            token_start = index + ch.len_utf8();
         }
-
-        token.push(ch);
     }
 
-    let tail = token.trim();
-    if !tail.is_empty() {
-        args.push(tail.to_owned());
-    }
+    push_trimmed_argument(&mut args, text, token_start, text.len(), base_offset);
 
     args
 }
 
+/// Trims whitespace off `text[start..end]` and, if anything is left, pushes
+/// it with a span (relative to `base_offset`) covering just the trimmed text.
+fn push_trimmed_argument(
+    args: &mut Vec<(String, ColumnSpan)>,
+    text: &str,
+    start: usize,
+    end: usize,
+    base_offset: usize,
+) {
+    let slice = &text[start..end];
+    let leading_whitespace = slice.len() - slice.trim_start().len();
+    let value = slice.trim();
+    if value.is_empty() {
+        return;
+    }
+
+    let value_start = base_offset + start + leading_whitespace;
+    let value_end = value_start + value.len();
+    args.push((value.to_owned(), ColumnSpan::new(value_start, value_end)));
+}
+
 fn normalize_operation(operation: &str) -> String {
     let op = operation.trim().trim_start_matches('.');
     op.to_ascii_uppercase()
@@ -311,7 +693,10 @@ fn classify_operation(operation: &str) -> StatementKind {
     }
 }
 
-fn count_db_bytes(arguments: &[String], line_no: usize) -> Result<usize, AssemblerError> {
+fn count_db_bytes(
+    arguments: &[String],
+    position: SourcePosition,
+) -> Result<usize, AssemblerError> {
     let mut total = 0;
 
     for argument in arguments {
@@ -323,7 +708,7 @@ fn count_db_bytes(arguments: &[String], line_no: usize) -> Result<usize, Assembl
     }
 
     if total == 0 {
-        return Err(AssemblerError::new("DB produced no bytes", Some(line_no)));
+        return Err(AssemblerError::new("DB produced no bytes", Some(position)));
     }
 
     Ok(total)
@@ -344,7 +729,7 @@ fn parse_string_literal(token: &str) -> Option<String> {
 fn encode_db_values(
     arguments: &[String],
     labels: &HashMap<String, usize>,
-    line_no: usize,
+    position: SourcePosition,
 ) -> Result<Vec<u8>, AssemblerError> {
     let mut values = Vec::new();
 
@@ -354,8 +739,8 @@ fn encode_db_values(
             continue;
         }
 
-        let byte = parse_value(argument, labels, line_no)?;
-        ensure_range(byte, 0, 0xFF, "byte", line_no)?;
+        let byte = parse_value(argument, labels, position)?;
+        ensure_range(byte, 0, 0xFF, "byte", position)?;
         values.push(byte as u8);
     }
 
@@ -0,0 +1,46 @@
+use crate::assembler::assembler::StatementKind;
+use crate::assembler::error::SourcePosition;
+
+/// Maps ranges of assembled byte addresses back to the source line (and
+/// statement kind) that emitted them, built alongside the bytes by
+/// `assemble_text_with_debug`/`assemble_file_with_debug`. Entries are in
+/// ascending address order, since `encode_statements` appends one per
+/// statement as it walks `current_address` forward. Intended for a future
+/// step-debugger front-end that wants to show "currently executing line N"
+/// by looking up the program counter (see `DebugController::current_line`).
+#[derive(Debug, Clone, Default)]
+pub struct DebugMap {
+    entries: Vec<DebugMapEntry>,
+}
+
+/// One assembled statement's address range, originating source line, and kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DebugMapEntry {
+    pub address_start: usize,
+    pub address_end: usize,
+    pub position: SourcePosition,
+    pub statement_kind: StatementKind,
+}
+
+impl DebugMap {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn push(&mut self, entry: DebugMapEntry) {
+        self.entries.push(entry);
+    }
+
+    /// All entries, in ascending address order.
+    pub fn entries(&self) -> &[DebugMapEntry] {
+        &self.entries
+    }
+
+    /// Finds the entry whose address range contains `address`, e.g. to look
+    /// up the source line a debugger's program counter currently points at.
+    pub fn lookup(&self, address: usize) -> Option<&DebugMapEntry> {
+        self.entries
+            .iter()
+            .find(|entry| (entry.address_start..entry.address_end).contains(&address))
+    }
+}
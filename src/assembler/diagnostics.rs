@@ -0,0 +1,35 @@
+use crate::assembler::error::AssemblerError;
+
+/// Renders one diagnostic as a source snippet with a caret underline pointing
+/// at its column span, e.g.:
+/// ```text
+/// file #0, line 3: unsupported LD form: V16, 1
+///     LD V16, 1
+///        ^^^^^^
+/// ```
+/// Falls back to the plain `Display` message when the error carries no
+/// snippet (e.g. an I/O error, or one raised before any line was read).
+pub fn format_diagnostic(error: &AssemblerError) -> String {
+    let header = error.to_string();
+    let (Some(snippet), Some(span)) = (&error.snippet, error.span) else {
+        return header;
+    };
+
+    let start = span.start.min(snippet.len());
+    let end = span.end.clamp(start, snippet.len());
+    let underline_width = (end - start).max(1);
+    let carets = "^".repeat(underline_width);
+
+    format!("{header}\n    {snippet}\n    {:start$}{carets}", "")
+}
+
+/// Renders every diagnostic in `errors`, separated by a blank line, so a
+/// whole assembly pass's worth of independent failures can be reported at
+/// once instead of just the first.
+pub fn format_diagnostics(errors: &[AssemblerError]) -> String {
+    errors
+        .iter()
+        .map(format_diagnostic)
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
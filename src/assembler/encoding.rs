@@ -1,6 +1,7 @@
-use crate::assembler::error::AssemblerError;
+use crate::assembler::error::{AssemblerError, SourcePosition};
+use crate::assembler::expression::evaluate_expression;
 
-pub fn parse_numeric_literal(token: &str, line_no: usize) -> Result<i32, AssemblerError> {
+pub fn parse_numeric_literal(token: &str, position: SourcePosition) -> Result<i32, AssemblerError> {
     let mut value = token.trim().to_owned();
     if let Some(rest) = value.strip_prefix('$') {
         value = format!("0x{rest}");
@@ -20,7 +21,7 @@ pub fn parse_numeric_literal(token: &str, line_no: usize) -> Result<i32, Assembl
 
     Err(AssemblerError::new(
         format!("invalid value '{value}'"),
-        Some(line_no),
+        Some(position),
     ))
 }
 
@@ -46,16 +47,15 @@ fn parse_prefixed_int(token: &str) -> Result<i32, std::num::ParseIntError> {
     token.parse::<i32>()
 }
 
+/// Evaluates an operand expression -- a single label/literal, or an
+/// arithmetic combination of them (`sprite_end - sprite_start`, `WIDTH * 2`)
+/// -- down to the `i32` that `ensure_range` then bounds-checks.
 pub fn parse_value(
     token: &str,
     labels: &std::collections::HashMap<String, usize>,
-    line_no: usize,
+    position: SourcePosition,
 ) -> Result<i32, AssemblerError> {
-    let token = token.trim();
-    if let Some(value) = labels.get(token) {
-        return Ok(*value as i32);
-    }
-    parse_numeric_literal(token, line_no)
+    Ok(evaluate_expression(token.trim(), labels, position)? as i32)
 }
 
 pub fn ensure_range(
@@ -63,21 +63,21 @@ pub fn ensure_range(
     minimum: i32,
     maximum: i32,
     label: &str,
-    line_no: usize,
+    position: SourcePosition,
 ) -> Result<(), AssemblerError> {
     if value < minimum || value > maximum {
         return Err(AssemblerError::new(
             format!("{label} out of range: {value} (expected {minimum}..{maximum})"),
-            Some(line_no),
+            Some(position),
         ));
     }
     Ok(())
 }
 
-pub fn parse_register(token: &str, line_no: usize) -> Result<u16, AssemblerError> {
+pub fn parse_register(token: &str, position: SourcePosition) -> Result<u16, AssemblerError> {
     let value = token.trim().to_ascii_uppercase();
     let reg_text = value.strip_prefix('V').ok_or_else(|| {
-        AssemblerError::new(format!("expected register, got '{token}'"), Some(line_no))
+        AssemblerError::new(format!("expected register, got '{token}'"), Some(position))
     })?;
 
     if reg_text.len() == 1 {
@@ -89,7 +89,7 @@ pub fn parse_register(token: &str, line_no: usize) -> Result<u16, AssemblerError
 
     if reg_text.chars().all(|ch| ch.is_ascii_digit()) {
         let reg = reg_text.parse::<u16>().map_err(|_| {
-            AssemblerError::new(format!("invalid register '{token}'"), Some(line_no))
+            AssemblerError::new(format!("invalid register '{token}'"), Some(position))
         })?;
         if reg <= 15 {
             return Ok(reg);
@@ -98,12 +98,12 @@ pub fn parse_register(token: &str, line_no: usize) -> Result<u16, AssemblerError
 
     Err(AssemblerError::new(
         format!("invalid register '{token}'"),
-        Some(line_no),
+        Some(position),
     ))
 }
 
 pub fn is_register(token: &str) -> bool {
-    parse_register(token, 0)
+    parse_register(token, SourcePosition::new(0, 0))
         .map(|reg| reg <= 15)
         .unwrap_or(false)
 }
@@ -112,7 +112,7 @@ fn expect_arg_count(
     mnemonic: &str,
     arguments: &[String],
     expected: usize,
-    line_no: usize,
+    position: SourcePosition,
 ) -> Result<(), AssemblerError> {
     if arguments.len() != expected {
         return Err(AssemblerError::new(
@@ -120,7 +120,7 @@ fn expect_arg_count(
                 "{mnemonic} expects {expected} argument(s), got {}",
                 arguments.len()
             ),
-            Some(line_no),
+            Some(position),
         ));
     }
     Ok(())
@@ -130,83 +130,110 @@ pub fn encode_instruction(
     mnemonic: &str,
     arguments: &[String],
     labels: &std::collections::HashMap<String, usize>,
-    line_no: usize,
+    position: SourcePosition,
 ) -> Result<u16, AssemblerError> {
     let op = mnemonic.to_ascii_uppercase();
 
     if op == "CLS" {
-        expect_arg_count(&op, arguments, 0, line_no)?;
+        expect_arg_count(&op, arguments, 0, position)?;
         return Ok(0x00E0);
     }
 
     if op == "RET" {
-        expect_arg_count(&op, arguments, 0, line_no)?;
+        expect_arg_count(&op, arguments, 0, position)?;
         return Ok(0x00EE);
     }
 
     if op == "EXIT" {
-        expect_arg_count(&op, arguments, 0, line_no)?;
+        expect_arg_count(&op, arguments, 0, position)?;
         return Ok(0x00FD);
     }
 
+    if op == "LOW" {
+        expect_arg_count(&op, arguments, 0, position)?;
+        return Ok(0x00FE);
+    }
+
+    if op == "HIGH" {
+        expect_arg_count(&op, arguments, 0, position)?;
+        return Ok(0x00FF);
+    }
+
+    if op == "SCR" {
+        expect_arg_count(&op, arguments, 0, position)?;
+        return Ok(0x00FB);
+    }
+
+    if op == "SCL" {
+        expect_arg_count(&op, arguments, 0, position)?;
+        return Ok(0x00FC);
+    }
+
+    if op == "SCD" {
+        expect_arg_count(&op, arguments, 1, position)?;
+        let n = parse_value(&arguments[0], labels, position)?;
+        ensure_range(n, 0, 0x000F, "nibble", position)?;
+        return Ok(0x00C0 | n as u16);
+    }
+
     if op == "JP" {
         if arguments.len() == 1 {
-            let address = parse_value(&arguments[0], labels, line_no)?;
-            ensure_range(address, 0, 0x0FFF, "address", line_no)?;
+            let address = parse_value(&arguments[0], labels, position)?;
+            ensure_range(address, 0, 0x0FFF, "address", position)?;
             return Ok(0x1000 | address as u16);
         }
         if arguments.len() == 2 {
-            let x_reg = parse_register(&arguments[0], line_no)?;
-            let nn = parse_value(&arguments[1], labels, line_no)?;
-            ensure_range(nn, 0, 0x00FF, "byte", line_no)?;
+            let x_reg = parse_register(&arguments[0], position)?;
+            let nn = parse_value(&arguments[1], labels, position)?;
+            ensure_range(nn, 0, 0x00FF, "byte", position)?;
             return Ok(0xB000 | (x_reg << 8) | nn as u16);
         }
         return Err(AssemblerError::new(
             "JP expects one or two arguments",
-            Some(line_no),
+            Some(position),
         ));
     }
 
     if op == "CALL" {
-        expect_arg_count(&op, arguments, 1, line_no)?;
-        let address = parse_value(&arguments[0], labels, line_no)?;
-        ensure_range(address, 0, 0x0FFF, "address", line_no)?;
+        expect_arg_count(&op, arguments, 1, position)?;
+        let address = parse_value(&arguments[0], labels, position)?;
+        ensure_range(address, 0, 0x0FFF, "address", position)?;
         return Ok(0x2000 | address as u16);
     }
 
     if op == "SE" {
-        expect_arg_count(&op, arguments, 2, line_no)?;
-        let x_reg = parse_register(&arguments[0], line_no)?;
+        expect_arg_count(&op, arguments, 2, position)?;
+        let x_reg = parse_register(&arguments[0], position)?;
         if is_register(&arguments[1]) {
-            let y_reg = parse_register(&arguments[1], line_no)?;
+            let y_reg = parse_register(&arguments[1], position)?;
             return Ok(0x5000 | (x_reg << 8) | (y_reg << 4));
         }
-        let nn = parse_value(&arguments[1], labels, line_no)?;
-        ensure_range(nn, 0, 0x00FF, "byte", line_no)?;
+        let nn = parse_value(&arguments[1], labels, position)?;
+        ensure_range(nn, 0, 0x00FF, "byte", position)?;
         return Ok(0x3000 | (x_reg << 8) | nn as u16);
     }
 
     if op == "SNE" {
-        expect_arg_count(&op, arguments, 2, line_no)?;
-        let x_reg = parse_register(&arguments[0], line_no)?;
+        expect_arg_count(&op, arguments, 2, position)?;
+        let x_reg = parse_register(&arguments[0], position)?;
         if is_register(&arguments[1]) {
-            let y_reg = parse_register(&arguments[1], line_no)?;
+            let y_reg = parse_register(&arguments[1], position)?;
             return Ok(0x9000 | (x_reg << 8) | (y_reg << 4));
         }
-        let nn = parse_value(&arguments[1], labels, line_no)?;
-        ensure_range(nn, 0, 0x00FF, "byte", line_no)?;
+        let nn = parse_value(&arguments[1], labels, position)?;
+        ensure_range(nn, 0, 0x00FF, "byte", position)?;
         return Ok(0x4000 | (x_reg << 8) | nn as u16);
     }
 
     if op == "LD" {
-        expect_arg_count(&op, arguments, 2, line_no)?;
+        expect_arg_count(&op, arguments, 2, position)?;
         let dest = arguments[0].trim().to_ascii_uppercase();
         let src = arguments[1].trim().to_ascii_uppercase();
 
         if is_register(&dest) {
-            let x_reg = parse_register(&dest, line_no)?;
+            let x_reg = parse_register(&dest, position)?;
             if is_register(&src) {
-                let y_reg = parse_register(&src, line_no)?;
+                let y_reg = parse_register(&src, position)?;
                 return Ok(0x8000 | (x_reg << 8) | (y_reg << 4));
             }
             if src == "DT" {
@@ -218,30 +245,36 @@ pub fn encode_instruction(
             if src == "[I]" {
                 return Ok(0xF065 | (x_reg << 8));
             }
-            let nn = parse_value(&arguments[1], labels, line_no)?;
-            ensure_range(nn, 0, 0x00FF, "byte", line_no)?;
+            if src == "R" {
+                return Ok(0xF085 | (x_reg << 8));
+            }
+            let nn = parse_value(&arguments[1], labels, position)?;
+            ensure_range(nn, 0, 0x00FF, "byte", position)?;
             return Ok(0x6000 | (x_reg << 8) | nn as u16);
         }
 
         if dest == "I" {
-            let address = parse_value(&arguments[1], labels, line_no)?;
-            ensure_range(address, 0, 0x0FFF, "address", line_no)?;
+            let address = parse_value(&arguments[1], labels, position)?;
+            ensure_range(address, 0, 0x0FFF, "address", position)?;
             return Ok(0xA000 | address as u16);
         }
         if dest == "DT" {
-            return Ok(0xF015 | (parse_register(&arguments[1], line_no)? << 8));
+            return Ok(0xF015 | (parse_register(&arguments[1], position)? << 8));
         }
         if dest == "ST" {
-            return Ok(0xF018 | (parse_register(&arguments[1], line_no)? << 8));
+            return Ok(0xF018 | (parse_register(&arguments[1], position)? << 8));
         }
         if dest == "F" {
-            return Ok(0xF029 | (parse_register(&arguments[1], line_no)? << 8));
+            return Ok(0xF029 | (parse_register(&arguments[1], position)? << 8));
         }
         if dest == "B" {
-            return Ok(0xF033 | (parse_register(&arguments[1], line_no)? << 8));
+            return Ok(0xF033 | (parse_register(&arguments[1], position)? << 8));
         }
         if dest == "[I]" {
-            return Ok(0xF055 | (parse_register(&arguments[1], line_no)? << 8));
+            return Ok(0xF055 | (parse_register(&arguments[1], position)? << 8));
+        }
+        if dest == "R" {
+            return Ok(0xF075 | (parse_register(&arguments[1], position)? << 8));
         }
 
         return Err(AssemblerError::new(
@@ -250,33 +283,33 @@ pub fn encode_instruction(
                 arguments[0].trim(),
                 arguments[1].trim()
             ),
-            Some(line_no),
+            Some(position),
         ));
     }
 
     if op == "ADD" {
-        expect_arg_count(&op, arguments, 2, line_no)?;
+        expect_arg_count(&op, arguments, 2, position)?;
         let dest = arguments[0].trim().to_ascii_uppercase();
 
         if dest == "I" {
-            return Ok(0xF01E | (parse_register(&arguments[1], line_no)? << 8));
+            return Ok(0xF01E | (parse_register(&arguments[1], position)? << 8));
         }
 
-        let x_reg = parse_register(&arguments[0], line_no)?;
+        let x_reg = parse_register(&arguments[0], position)?;
         if is_register(&arguments[1]) {
-            let y_reg = parse_register(&arguments[1], line_no)?;
+            let y_reg = parse_register(&arguments[1], position)?;
             return Ok(0x8004 | (x_reg << 8) | (y_reg << 4));
         }
 
-        let nn = parse_value(&arguments[1], labels, line_no)?;
-        ensure_range(nn, 0, 0x00FF, "byte", line_no)?;
+        let nn = parse_value(&arguments[1], labels, position)?;
+        ensure_range(nn, 0, 0x00FF, "byte", position)?;
         return Ok(0x7000 | (x_reg << 8) | nn as u16);
     }
 
     if ["OR", "AND", "XOR", "SUB", "SUBN"].contains(&op.as_str()) {
-        expect_arg_count(&op, arguments, 2, line_no)?;
-        let x_reg = parse_register(&arguments[0], line_no)?;
-        let y_reg = parse_register(&arguments[1], line_no)?;
+        expect_arg_count(&op, arguments, 2, position)?;
+        let x_reg = parse_register(&arguments[0], position)?;
+        let y_reg = parse_register(&arguments[1], position)?;
         let tail = match op.as_str() {
             "OR" => 0x1,
             "AND" => 0x2,
@@ -292,12 +325,12 @@ pub fn encode_instruction(
         if arguments.len() != 1 && arguments.len() != 2 {
             return Err(AssemblerError::new(
                 format!("{op} expects one or two arguments"),
-                Some(line_no),
+                Some(position),
             ));
         }
-        let x_reg = parse_register(&arguments[0], line_no)?;
+        let x_reg = parse_register(&arguments[0], position)?;
         let y_reg = if arguments.len() == 2 {
-            parse_register(&arguments[1], line_no)?
+            parse_register(&arguments[1], position)?
         } else {
             x_reg
         };
@@ -306,36 +339,36 @@ pub fn encode_instruction(
     }
 
     if op == "RND" {
-        expect_arg_count(&op, arguments, 2, line_no)?;
-        let x_reg = parse_register(&arguments[0], line_no)?;
-        let nn = parse_value(&arguments[1], labels, line_no)?;
-        ensure_range(nn, 0, 0x00FF, "byte", line_no)?;
+        expect_arg_count(&op, arguments, 2, position)?;
+        let x_reg = parse_register(&arguments[0], position)?;
+        let nn = parse_value(&arguments[1], labels, position)?;
+        ensure_range(nn, 0, 0x00FF, "byte", position)?;
         return Ok(0xC000 | (x_reg << 8) | nn as u16);
     }
 
     if op == "DRW" {
-        expect_arg_count(&op, arguments, 3, line_no)?;
-        let x_reg = parse_register(&arguments[0], line_no)?;
-        let y_reg = parse_register(&arguments[1], line_no)?;
-        let n = parse_value(&arguments[2], labels, line_no)?;
-        ensure_range(n, 0, 0x000F, "nibble", line_no)?;
+        expect_arg_count(&op, arguments, 3, position)?;
+        let x_reg = parse_register(&arguments[0], position)?;
+        let y_reg = parse_register(&arguments[1], position)?;
+        let n = parse_value(&arguments[2], labels, position)?;
+        ensure_range(n, 0, 0x000F, "nibble", position)?;
         return Ok(0xD000 | (x_reg << 8) | (y_reg << 4) | n as u16);
     }
 
     if op == "SKP" {
-        expect_arg_count(&op, arguments, 1, line_no)?;
-        let x_reg = parse_register(&arguments[0], line_no)?;
+        expect_arg_count(&op, arguments, 1, position)?;
+        let x_reg = parse_register(&arguments[0], position)?;
         return Ok(0xE09E | (x_reg << 8));
     }
 
     if op == "SKNP" {
-        expect_arg_count(&op, arguments, 1, line_no)?;
-        let x_reg = parse_register(&arguments[0], line_no)?;
+        expect_arg_count(&op, arguments, 1, position)?;
+        let x_reg = parse_register(&arguments[0], position)?;
         return Ok(0xE0A1 | (x_reg << 8));
     }
 
     Err(AssemblerError::new(
         format!("unknown instruction '{mnemonic}'"),
-        Some(line_no),
+        Some(position),
     ))
 }
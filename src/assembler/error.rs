@@ -1,24 +1,73 @@
 use std::fmt::{Display, Formatter};
 
+/// Identifies a single line in a specific source file. `file_index` indexes
+/// into the preprocessor's file table (index 0 is always the top-level file
+/// passed to `assemble_file`/`assemble_text`); `line` is the 1-based line
+/// number within that file. Once `#include` splicing and `%macro` expansion
+/// have flattened a program into one statement stream, this is what lets an
+/// error point back at the file it actually came from instead of an offset
+/// into the flattened stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourcePosition {
+    pub file_index: usize,
+    pub line: usize,
+}
+
+impl SourcePosition {
+    pub fn new(file_index: usize, line: usize) -> Self {
+        Self { file_index, line }
+    }
+}
+
+/// A byte-offset range within a single source line, used to underline the
+/// offending token in a rendered diagnostic (see `diagnostics::format_diagnostic`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColumnSpan {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl ColumnSpan {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct AssemblerError {
     pub message: String,
-    pub line_no: Option<usize>,
+    pub position: Option<SourcePosition>,
+    pub snippet: Option<String>,
+    pub span: Option<ColumnSpan>,
 }
 
 impl AssemblerError {
-    pub fn new(message: impl Into<String>, line_no: Option<usize>) -> Self {
+    pub fn new(message: impl Into<String>, position: Option<SourcePosition>) -> Self {
         Self {
             message: message.into(),
-            line_no,
+            position,
+            snippet: None,
+            span: None,
         }
     }
+
+    /// Attaches the offending source line and the column span within it that
+    /// a rendered diagnostic should underline.
+    pub fn with_snippet(mut self, snippet: impl Into<String>, span: ColumnSpan) -> Self {
+        self.snippet = Some(snippet.into());
+        self.span = Some(span);
+        self
+    }
 }
 
 impl Display for AssemblerError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        if let Some(line_no) = self.line_no {
-            write!(f, "line {line_no}: {}", self.message)
+        if let Some(position) = self.position {
+            write!(
+                f,
+                "file #{}, line {}: {}",
+                position.file_index, position.line, self.message
+            )
         } else {
             write!(f, "{}", self.message)
         }
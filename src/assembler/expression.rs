@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+
+use crate::assembler::encoding::parse_numeric_literal;
+use crate::assembler::error::{AssemblerError, SourcePosition};
+
+/// Recursive-descent evaluator for operand expressions: `+ - * / %`, unary
+/// minus, parenthesization, and the numeric literal syntaxes `parse_value`
+/// already accepted (hex/bin/oct/`$`/char), plus symbol lookups against
+/// `labels` -- which holds both label addresses and `EQU` constants, since
+/// the two share one namespace. This is what lets an operand be written as
+/// `sprite_end - sprite_start` or `WIDTH * 2` instead of a single literal or
+/// bare label.
+pub fn evaluate_expression(
+    text: &str,
+    labels: &HashMap<String, usize>,
+    position: SourcePosition,
+) -> Result<i64, AssemblerError> {
+    let mut parser = Parser {
+        chars: text.chars().collect(),
+        pos: 0,
+        labels,
+        position,
+    };
+    let value = parser.parse_expr()?;
+    if parser.peek().is_some() {
+        return Err(AssemblerError::new(
+            format!("unexpected trailing characters in expression '{text}'"),
+            Some(position),
+        ));
+    }
+    Ok(value)
+}
+
+struct Parser<'a> {
+    chars: Vec<char>,
+    pos: usize,
+    labels: &'a HashMap<String, usize>,
+    position: SourcePosition,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        while matches!(self.chars.get(self.pos), Some(ch) if ch.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.chars.get(self.pos).copied()
+    }
+
+    fn parse_expr(&mut self) -> Result<i64, AssemblerError> {
+        let mut value = self.parse_term()?;
+        loop {
+            match self.peek() {
+                Some('+') => {
+                    self.pos += 1;
+                    value += self.parse_term()?;
+                }
+                Some('-') => {
+                    self.pos += 1;
+                    value -= self.parse_term()?;
+                }
+                _ => return Ok(value),
+            }
+        }
+    }
+
+    fn parse_term(&mut self) -> Result<i64, AssemblerError> {
+        let mut value = self.parse_unary()?;
+        loop {
+            match self.peek() {
+                Some('*') => {
+                    self.pos += 1;
+                    value *= self.parse_unary()?;
+                }
+                Some('/') => {
+                    self.pos += 1;
+                    let rhs = self.parse_unary()?;
+                    value = self.checked_divide(value, rhs, false)?;
+                }
+                Some('%') => {
+                    self.pos += 1;
+                    let rhs = self.parse_unary()?;
+                    value = self.checked_divide(value, rhs, true)?;
+                }
+                _ => return Ok(value),
+            }
+        }
+    }
+
+    fn checked_divide(&self, lhs: i64, rhs: i64, is_modulo: bool) -> Result<i64, AssemblerError> {
+        if rhs == 0 {
+            return Err(AssemblerError::new(
+                "division by zero in expression",
+                Some(self.position),
+            ));
+        }
+        Ok(if is_modulo { lhs % rhs } else { lhs / rhs })
+    }
+
+    fn parse_unary(&mut self) -> Result<i64, AssemblerError> {
+        match self.peek() {
+            Some('-') => {
+                self.pos += 1;
+                Ok(-self.parse_unary()?)
+            }
+            Some('+') => {
+                self.pos += 1;
+                self.parse_unary()
+            }
+            _ => self.parse_primary(),
+        }
+    }
+
+    fn parse_primary(&mut self) -> Result<i64, AssemblerError> {
+        match self.peek() {
+            Some('(') => {
+                self.pos += 1;
+                let value = self.parse_expr()?;
+                match self.peek() {
+                    Some(')') => {
+                        self.pos += 1;
+                        Ok(value)
+                    }
+                    _ => Err(AssemblerError::new(
+                        "expected ')' in expression",
+                        Some(self.position),
+                    )),
+                }
+            }
+            Some(_) => self.parse_atom(),
+            None => Err(AssemblerError::new(
+                "expected a value in expression",
+                Some(self.position),
+            )),
+        }
+    }
+
+    fn parse_atom(&mut self) -> Result<i64, AssemblerError> {
+        let start = self.pos;
+
+        if self.chars.get(self.pos) == Some(&'\'') {
+            self.pos += 1;
+            while matches!(self.chars.get(self.pos), Some(ch) if *ch != '\'') {
+                self.pos += 1;
+            }
+            if self.chars.get(self.pos) == Some(&'\'') {
+                self.pos += 1;
+            }
+        } else {
+            if self.chars.get(self.pos) == Some(&'$') {
+                self.pos += 1;
+            }
+            while matches!(self.chars.get(self.pos), Some(ch) if ch.is_ascii_alphanumeric() || *ch == '_')
+            {
+                self.pos += 1;
+            }
+        }
+
+        let token: String = self.chars[start..self.pos].iter().collect();
+        if token.is_empty() {
+            return Err(AssemblerError::new(
+                "expected a value in expression",
+                Some(self.position),
+            ));
+        }
+
+        if let Some(&value) = self.labels.get(&token) {
+            return Ok(value as i64);
+        }
+
+        Ok(parse_numeric_literal(&token, self.position)? as i64)
+    }
+}
@@ -1,6 +1,19 @@
 pub mod assembler;
+pub mod debug_map;
+pub mod diagnostics;
 pub mod encoding;
 pub mod error;
+pub mod expression;
+pub mod preprocessor;
+pub mod resolver;
 
-pub use assembler::{assemble_file, assemble_text};
-pub use error::AssemblerError;
+pub use assembler::{
+    assemble_file, assemble_file_with_debug, assemble_file_with_diagnostics, assemble_text,
+    assemble_text_with_debug, assemble_text_with_diagnostics, assemble_text_with_resolver,
+    StatementKind,
+};
+pub use debug_map::{DebugMap, DebugMapEntry};
+pub use diagnostics::{format_diagnostic, format_diagnostics};
+pub use error::{AssemblerError, ColumnSpan, SourcePosition};
+pub use preprocessor::{preprocess_with_resolver, Preprocessor};
+pub use resolver::{FilesystemResolver, InMemoryResolver, SourceResolver};
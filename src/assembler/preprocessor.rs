@@ -0,0 +1,466 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::assembler::error::{AssemblerError, SourcePosition};
+use crate::assembler::resolver::{FilesystemResolver, SourceResolver};
+
+/// Expands `#include "path"`/`INCLUDE "path"` directives (two spellings of
+/// the same thing) and macro blocks (`%macro NAME arg0 arg1 ... / %endmacro`,
+/// or the equivalent `MACRO NAME arg0, arg1 ... / ENDM` spelling) into a flat
+/// stream of `(SourcePosition, String)` lines, so `parse_source`/
+/// `encode_statements` never have to know a program came from more than one
+/// file or used a macro at all. Include cycles are rejected by tracking the
+/// files currently being expanded; macro bodies are expanded textually at
+/// each call site, with `\0`, `\1`, ... substituted for the call's arguments
+/// in order, and any label the body defines renamed with a per-invocation
+/// suffix so the same macro can be called more than once without colliding
+/// labels. A macro invoking itself, directly or through another macro, is
+/// rejected via an expansion stack.
+pub struct Preprocessor {
+    file_table: Vec<String>,
+}
+
+impl Preprocessor {
+    pub fn file_name(&self, file_index: usize) -> &str {
+        self.file_table
+            .get(file_index)
+            .map(String::as_str)
+            .unwrap_or("<unknown>")
+    }
+}
+
+#[derive(Clone)]
+struct MacroDef {
+    params: Vec<String>,
+    body: Vec<String>,
+    label_names: Vec<String>,
+}
+
+pub fn preprocess(
+    source: &str,
+    source_name: &str,
+    base_dir: Option<&Path>,
+) -> Result<(Vec<(SourcePosition, String)>, Preprocessor), AssemblerError> {
+    preprocess_with_resolver(source, source_name, base_dir, Box::new(FilesystemResolver))
+}
+
+/// Like `preprocess`, but reads `#include`/`INCLUDE` targets through
+/// `resolver` instead of always going to disk. `assemble_text`/`assemble_file`
+/// use the default `FilesystemResolver`; pass an `InMemoryResolver` to
+/// assemble a multi-file program from strings alone.
+pub fn preprocess_with_resolver(
+    source: &str,
+    source_name: &str,
+    base_dir: Option<&Path>,
+    resolver: Box<dyn SourceResolver>,
+) -> Result<(Vec<(SourcePosition, String)>, Preprocessor), AssemblerError> {
+    let top_level_identity = base_dir
+        .map(|dir| dir.join(source_name))
+        .unwrap_or_else(|| PathBuf::from(source_name));
+    let top_level_identity = top_level_identity
+        .canonicalize()
+        .unwrap_or(top_level_identity)
+        .display()
+        .to_string();
+
+    let mut state = ExpansionState {
+        file_table: vec![source_name.to_owned()],
+        include_stack: vec![top_level_identity],
+        macros: HashMap::new(),
+        expansion_stack: Vec::new(),
+        invocation_counter: 0,
+        resolver,
+    };
+    let mut output = Vec::new();
+    state.expand(source, 0, base_dir, &mut output)?;
+    Ok((
+        output,
+        Preprocessor {
+            file_table: state.file_table,
+        },
+    ))
+}
+
+struct ExpansionState {
+    file_table: Vec<String>,
+    include_stack: Vec<String>,
+    macros: HashMap<String, MacroDef>,
+    expansion_stack: Vec<String>,
+    invocation_counter: usize,
+    resolver: Box<dyn SourceResolver>,
+}
+
+impl ExpansionState {
+    fn expand(
+        &mut self,
+        source: &str,
+        file_index: usize,
+        base_dir: Option<&Path>,
+        output: &mut Vec<(SourcePosition, String)>,
+    ) -> Result<(), AssemblerError> {
+        let mut lines = source.lines().enumerate();
+
+        while let Some((line_index, raw_line)) = lines.next() {
+            let position = SourcePosition::new(file_index, line_index + 1);
+            self.process_line(raw_line, position, base_dir, &mut lines, output)?;
+        }
+
+        Ok(())
+    }
+
+    /// Handles one source line: `#include`, a macro definition header, a
+    /// macro invocation, or a plain line passed through untouched. Shared by
+    /// the top-level file scan and by macro-body expansion, so a macro body
+    /// can itself invoke another macro.
+    fn process_line(
+        &mut self,
+        raw_line: &str,
+        position: SourcePosition,
+        base_dir: Option<&Path>,
+        lines: &mut std::iter::Enumerate<std::str::Lines<'_>>,
+        output: &mut Vec<(SourcePosition, String)>,
+    ) -> Result<(), AssemblerError> {
+        let trimmed = raw_line.trim();
+
+        if let Some(rest) = trimmed.strip_prefix("#include") {
+            self.expand_include(rest, position, base_dir, output, "#include")?;
+            return Ok(());
+        }
+
+        if let Some(rest) = strip_include_keyword(trimmed) {
+            self.expand_include(rest, position, base_dir, output, "INCLUDE")?;
+            return Ok(());
+        }
+
+        if let Some(header) = macro_header(trimmed) {
+            self.define_macro(header, lines, position)?;
+            return Ok(());
+        }
+
+        let call_name = trimmed.split_whitespace().next().unwrap_or("");
+        if self.macros.contains_key(call_name) {
+            self.expand_macro_call(call_name, trimmed, position, base_dir, output)?;
+            return Ok(());
+        }
+
+        output.push((position, raw_line.to_owned()));
+        Ok(())
+    }
+
+    fn expand_include(
+        &mut self,
+        rest: &str,
+        position: SourcePosition,
+        base_dir: Option<&Path>,
+        output: &mut Vec<(SourcePosition, String)>,
+        directive: &str,
+    ) -> Result<(), AssemblerError> {
+        let path_text = parse_quoted_argument(rest, directive, position)?;
+        let resolved = resolve_include_path(&path_text, base_dir);
+        let identity = resolved
+            .canonicalize()
+            .unwrap_or_else(|_| resolved.clone())
+            .display()
+            .to_string();
+
+        if self.include_stack.contains(&identity) {
+            return Err(AssemblerError::new(
+                format!("include cycle detected: '{identity}'"),
+                Some(position),
+            ));
+        }
+
+        let included_source = self
+            .resolver
+            .resolve(&resolved.display().to_string())
+            .map_err(|error| AssemblerError::new(error.message, Some(position)))?;
+
+        let included_index = self.file_table.len();
+        self.file_table.push(resolved.display().to_string());
+        self.include_stack.push(identity);
+
+        let included_base_dir = resolved.parent().map(Path::to_path_buf);
+        self.expand(
+            &included_source,
+            included_index,
+            included_base_dir.as_deref(),
+            output,
+        )?;
+
+        self.include_stack.pop();
+        Ok(())
+    }
+
+    fn define_macro(
+        &mut self,
+        header: &str,
+        lines: &mut std::iter::Enumerate<std::str::Lines<'_>>,
+        position: SourcePosition,
+    ) -> Result<(), AssemblerError> {
+        let mut header_parts = header.trim().splitn(2, char::is_whitespace);
+        let name = header_parts
+            .next()
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| AssemblerError::new("macro definition requires a name", Some(position)))?
+            .to_owned();
+
+        if self.macros.contains_key(&name) {
+            return Err(AssemblerError::new(
+                format!("duplicate macro definition '{name}'"),
+                Some(position),
+            ));
+        }
+
+        let params = split_macro_arguments(header_parts.next().unwrap_or(""));
+
+        let mut body = Vec::new();
+        loop {
+            let Some((_, body_line)) = lines.next() else {
+                return Err(AssemblerError::new(
+                    format!("unterminated macro '{name}'"),
+                    Some(position),
+                ));
+            };
+            let body_trimmed = body_line.trim();
+            if is_macro_end(body_trimmed) {
+                break;
+            }
+            if macro_header(body_trimmed).is_some() {
+                return Err(AssemblerError::new(
+                    format!("nested macro definition inside '{name}'"),
+                    Some(position),
+                ));
+            }
+            body.push(body_line.to_owned());
+        }
+
+        let label_names = body
+            .iter()
+            .filter_map(|line| defined_label_name(line.trim()))
+            .collect();
+
+        self.macros.insert(
+            name,
+            MacroDef {
+                params,
+                body,
+                label_names,
+            },
+        );
+        Ok(())
+    }
+
+    fn expand_macro_call(
+        &mut self,
+        call_name: &str,
+        trimmed: &str,
+        position: SourcePosition,
+        base_dir: Option<&Path>,
+        output: &mut Vec<(SourcePosition, String)>,
+    ) -> Result<(), AssemblerError> {
+        if self.expansion_stack.iter().any(|name| name == call_name) {
+            return Err(AssemblerError::new(
+                format!(
+                    "recursive macro expansion: '{call_name}' (call stack: {})",
+                    self.expansion_stack.join(" -> ")
+                ),
+                Some(position),
+            ));
+        }
+
+        let macro_def = self.macros.get(call_name).expect("checked by caller").clone();
+        let call_args = split_macro_arguments(trimmed[call_name.len()..].trim());
+        if call_args.len() != macro_def.params.len() {
+            return Err(AssemblerError::new(
+                format!(
+                    "macro '{call_name}' expects {} argument(s), got {}",
+                    macro_def.params.len(),
+                    call_args.len()
+                ),
+                Some(position),
+            ));
+        }
+
+        self.invocation_counter += 1;
+        let suffix = format!("__{call_name}{}", self.invocation_counter);
+
+        let expanded_lines = expand_macro_body(&macro_def, &call_args, &suffix);
+
+        self.expansion_stack.push(call_name.to_owned());
+        for body_line in expanded_lines {
+            let mut body_lines = "".lines().enumerate();
+            self.process_line(&body_line, position, base_dir, &mut body_lines, output)?;
+        }
+        self.expansion_stack.pop();
+
+        Ok(())
+    }
+}
+
+/// Returns the directive text after `INCLUDE`, if `trimmed` opens an
+/// `INCLUDE "path"` directive. Case-insensitive, matching `MACRO`/`ENDM`.
+fn strip_include_keyword(trimmed: &str) -> Option<&str> {
+    let mut parts = trimmed.splitn(2, char::is_whitespace);
+    if parts.next()?.eq_ignore_ascii_case("INCLUDE") {
+        return Some(parts.next().unwrap_or(""));
+    }
+    None
+}
+
+/// Returns the directive text after `MACRO`/`%macro`, if `trimmed` opens a
+/// macro definition.
+fn macro_header(trimmed: &str) -> Option<&str> {
+    if let Some(rest) = trimmed.strip_prefix("%macro") {
+        return Some(rest);
+    }
+    let mut parts = trimmed.splitn(2, char::is_whitespace);
+    if parts.next()?.eq_ignore_ascii_case("MACRO") {
+        return Some(parts.next().unwrap_or(""));
+    }
+    None
+}
+
+fn is_macro_end(trimmed: &str) -> bool {
+    trimmed == "%endmacro" || trimmed.eq_ignore_ascii_case("ENDM")
+}
+
+/// If `trimmed` is (only) a label definition (`name:`), returns `name`.
+fn defined_label_name(trimmed: &str) -> Option<String> {
+    let colon_index = trimmed.find(':')?;
+    let before = &trimmed[..colon_index];
+    if before.is_empty() || before.chars().any(char::is_whitespace) {
+        return None;
+    }
+    Some(before.to_owned())
+}
+
+fn parse_quoted_argument(
+    rest: &str,
+    directive: &str,
+    position: SourcePosition,
+) -> Result<String, AssemblerError> {
+    let rest = rest.trim();
+    if rest.len() >= 2 && rest.starts_with('"') && rest.ends_with('"') {
+        Ok(rest[1..rest.len() - 1].to_owned())
+    } else {
+        Err(AssemblerError::new(
+            format!("{directive} expects a quoted path, e.g. {directive} \"other.asm\""),
+            Some(position),
+        ))
+    }
+}
+
+fn resolve_include_path(path_text: &str, base_dir: Option<&Path>) -> PathBuf {
+    let path = Path::new(path_text);
+    if path.is_absolute() {
+        return path.to_path_buf();
+    }
+    match base_dir {
+        Some(dir) => dir.join(path),
+        None => path.to_path_buf(),
+    }
+}
+
+fn split_macro_arguments(text: &str) -> Vec<String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Vec::new();
+    }
+    if text.contains(',') {
+        text.split(',').map(|arg| arg.trim().to_owned()).collect()
+    } else {
+        text.split_whitespace().map(str::to_owned).collect()
+    }
+}
+
+/// Replaces every whole-word occurrence of `name` in `line` with `name{suffix}`.
+fn rename_identifier(line: &str, name: &str, suffix: &str) -> String {
+    let mut result = String::with_capacity(line.len());
+    let mut chars = line.char_indices().peekable();
+
+    while let Some((index, ch)) = chars.next() {
+        if !(ch.is_ascii_alphabetic() || ch == '_') {
+            result.push(ch);
+            continue;
+        }
+
+        let start = index;
+        let mut end = index + ch.len_utf8();
+        while let Some(&(next_index, next_ch)) = chars.peek() {
+            if next_ch.is_ascii_alphanumeric() || next_ch == '_' {
+                end = next_index + next_ch.len_utf8();
+                chars.next();
+            } else {
+                break;
+            }
+        }
+
+        let word = &line[start..end];
+        if word == name {
+            result.push_str(name);
+            result.push_str(suffix);
+        } else {
+            result.push_str(word);
+        }
+    }
+
+    result
+}
+
+/// Substitutes `\0`, `\1`, ... in `line` for `call_args`, in a single
+/// left-to-right pass that reads the full digit run after each `\` before
+/// looking up the argument. Doing this one index at a time with
+/// `str::replace` (as before) is unsound: `\1` matches as a prefix of `\10`,
+/// so a macro called with 10+ positional arguments would have `\10` expand
+/// to argument 1 followed by a literal `0`.
+fn substitute_positional_args(line: &str, call_args: &[String]) -> String {
+    let mut result = String::with_capacity(line.len());
+    let mut chars = line.char_indices().peekable();
+
+    while let Some((index, ch)) = chars.next() {
+        if ch != '\\' {
+            result.push(ch);
+            continue;
+        }
+
+        let digits_start = index + 1;
+        let mut digits_end = digits_start;
+        while let Some(&(next_index, next_ch)) = chars.peek() {
+            if next_ch.is_ascii_digit() {
+                digits_end = next_index + next_ch.len_utf8();
+                chars.next();
+            } else {
+                break;
+            }
+        }
+
+        if digits_end == digits_start {
+            result.push(ch);
+            continue;
+        }
+
+        let arg_index: usize = line[digits_start..digits_end]
+            .parse()
+            .expect("only ascii digits were consumed above");
+        match call_args.get(arg_index) {
+            Some(arg) => result.push_str(arg),
+            None => result.push_str(&line[index..digits_end]),
+        }
+    }
+
+    result
+}
+
+fn expand_macro_body(macro_def: &MacroDef, call_args: &[String], suffix: &str) -> Vec<String> {
+    macro_def
+        .body
+        .iter()
+        .map(|body_line| {
+            let mut line = substitute_positional_args(body_line, call_args);
+            for label in &macro_def.label_names {
+                line = rename_identifier(&line, label, suffix);
+            }
+            line
+        })
+        .collect()
+}
@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::assembler::error::AssemblerError;
+
+/// Resolves the source text an `#include`/`INCLUDE` directive's target
+/// refers to. The preprocessor calls this with the target already joined
+/// against the including file's directory, so a resolver only needs to
+/// decide how to turn that string into source text. Swapping in
+/// `InMemoryResolver` in place of the default `FilesystemResolver` lets
+/// `assemble_text` use includes without touching disk, which is what makes
+/// include behavior testable without temp files.
+pub trait SourceResolver {
+    fn resolve(&self, path: &str) -> Result<String, AssemblerError>;
+}
+
+/// Reads `path` from disk. The resolver `preprocess` uses by default.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FilesystemResolver;
+
+impl SourceResolver for FilesystemResolver {
+    fn resolve(&self, path: &str) -> Result<String, AssemblerError> {
+        fs::read_to_string(Path::new(path)).map_err(|error| {
+            AssemblerError::new(format!("could not read included file '{path}': {error}"), None)
+        })
+    }
+}
+
+/// Serves include targets from an in-memory map instead of the filesystem,
+/// for programs assembled entirely from strings.
+#[derive(Debug, Default, Clone)]
+pub struct InMemoryResolver {
+    files: HashMap<String, String>,
+}
+
+impl InMemoryResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_file(mut self, name: impl Into<String>, source: impl Into<String>) -> Self {
+        self.files.insert(name.into(), source.into());
+        self
+    }
+}
+
+impl SourceResolver for InMemoryResolver {
+    fn resolve(&self, path: &str) -> Result<String, AssemblerError> {
+        self.files.get(path).cloned().ok_or_else(|| {
+            AssemblerError::new(format!("no in-memory file registered for '{path}'"), None)
+        })
+    }
+}
@@ -0,0 +1,53 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use chip8_emulator_rs::assembler::AssemblerError;
+use chip8_emulator_rs::disassembler::disassemble_file;
+
+#[derive(Debug, Parser)]
+#[command(name = "chip8-disasm")]
+#[command(about = "Disassemble a CHIP-8 ROM into assembly source")]
+struct Args {
+    rom: PathBuf,
+
+    #[arg(short = 'o', long)]
+    output: Option<PathBuf>,
+
+    #[arg(long, default_value = "0x200")]
+    origin: String,
+}
+
+fn parse_origin(text: &str) -> Result<usize, AssemblerError> {
+    let value = if let Some(rest) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        usize::from_str_radix(rest, 16)
+            .map_err(|_| AssemblerError::new(format!("invalid --origin value '{text}'"), None))?
+    } else {
+        text.parse::<usize>()
+            .map_err(|_| AssemblerError::new(format!("invalid --origin value '{text}'"), None))?
+    };
+    Ok(value)
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+
+    let output_path = args
+        .output
+        .unwrap_or_else(|| args.rom.with_extension("asm"));
+
+    let origin = parse_origin(&args.origin)?;
+    let source = disassemble_file(&args.rom, origin)?;
+
+    if let Some(parent) = output_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&output_path, &source)?;
+
+    println!(
+        "wrote {} bytes to {}",
+        source.len(),
+        output_path.display()
+    );
+    Ok(())
+}
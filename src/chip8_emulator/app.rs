@@ -1,17 +1,20 @@
 use std::path::Path;
 use std::time::Instant;
 
-use crate::chip8_emulator::config::{MEMORY_SIZE, SCREEN_HEIGHT, SCREEN_WIDTH};
-use crate::chip8_emulator::cpu::{execute_cycle, tick_timers};
+use crate::chip8_emulator::config::{HIRES_SCREEN_HEIGHT, HIRES_SCREEN_WIDTH};
+use crate::chip8_emulator::cpu::{execute_cycle, tick_timers, CycleScheduler};
+use crate::chip8_emulator::debug::{format_post_mortem_trace, DebugController};
 use crate::chip8_emulator::error::Chip8Error;
 use crate::chip8_emulator::quirks::Chip8Quirks;
-use crate::chip8_emulator::state::{create_state, set_key_state, EmulatorState};
+use crate::chip8_emulator::save_state::{load_state, quick_save_path, save_state};
+use crate::chip8_emulator::state::{composite_planes, create_state, set_key_state, EmulatorState};
 
 pub fn run_emulator_headless(
     quirks: Chip8Quirks,
     rom_path: &Path,
     max_cycles: usize,
     cpu_hz: usize,
+    seed: Option<u64>,
 ) -> Result<EmulatorState, Chip8Error> {
     if max_cycles == 0 {
         return Err(Chip8Error::InvalidArgument("max_cycles must be > 0"));
@@ -20,19 +23,16 @@ pub fn run_emulator_headless(
         return Err(Chip8Error::InvalidArgument("cpu_hz must be > 0"));
     }
 
-    let mut state = create_state(Some(rom_path))?;
-    let cycles_per_timer_tick = usize::max(1, cpu_hz / 60);
+    let mut state = create_state(Some(rom_path), seed)?;
+    let scheduler = CycleScheduler::new(usize::max(1, cpu_hz / 60));
 
-    for i in 0..max_cycles {
-        if state.exited {
-            break;
-        }
-
-        execute_cycle(&mut state, quirks)?;
-
-        if ((i + 1) % cycles_per_timer_tick) == 0 {
-            tick_timers(&mut state, None);
+    let mut cycles_run = 0;
+    while cycles_run < max_cycles && !state.exited {
+        if let Err(error) = scheduler.run_frame(&mut state, quirks, None) {
+            eprintln!("{}", format_post_mortem_trace(&state));
+            return Err(error);
         }
+        cycles_run += scheduler.cycles_per_frame();
     }
 
     Ok(state)
@@ -44,6 +44,7 @@ pub fn run_emulator_app(
     scale: usize,
     cpu_hz: usize,
     target_fps: usize,
+    seed: Option<u64>,
 ) -> Result<EmulatorState, Chip8Error> {
     use raylib::prelude::{Color, KeyboardKey, RaylibDraw};
 
@@ -57,10 +58,12 @@ pub fn run_emulator_app(
         return Err(Chip8Error::InvalidArgument("target_fps must be > 0"));
     }
 
-    let mut state = create_state(Some(rom_path))?;
+    let mut state = create_state(Some(rom_path), seed)?;
 
-    let width = (SCREEN_WIDTH * scale) as i32;
-    let height = (SCREEN_HEIGHT * scale) as i32;
+    // Sized for the larger Super-CHIP hi-res mode so switching resolution
+    // mid-ROM never requires resizing the window.
+    let width = (HIRES_SCREEN_WIDTH * scale) as i32;
+    let height = (HIRES_SCREEN_HEIGHT * scale) as i32;
     let (mut rl, thread) = raylib::init()
         .size(width, height)
         .title("chip8-emulator-rs")
@@ -91,15 +94,35 @@ pub fn run_emulator_app(
     let max_cycles_per_frame = usize::max(1, (cpu_hz / target_fps) * 3);
     let mut accumulated_time = 0.0f32;
     let mut timer_accumulated_time = 0.0f32;
-    let mut front_buffer = state.screen_buffer;
+    let mut front_buffer = composite_planes(&state);
+    let mut front_buffer_width = state.width;
     let mut previous_tick = Instant::now();
-    let mut frame_in_progress_after_clear = false;
-    let mut has_draw_since_clear = false;
+    let mut debugger = DebugController::new();
 
     while !rl.window_should_close() && !state.exited {
         if rl.is_key_pressed(KeyboardKey::KEY_ESCAPE) {
             break;
         }
+        if rl.is_key_pressed(KeyboardKey::KEY_SPACE) {
+            debugger.toggle_pause();
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_N) {
+            debugger.request_step();
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_F5) {
+            if let Some(rom_path) = state.rom_path.clone() {
+                let _ = save_state(&state, &quick_save_path(&rom_path));
+            }
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_F9) {
+            if let Some(rom_path) = state.rom_path.clone() {
+                if let Ok(loaded) = load_state(&quick_save_path(&rom_path)) {
+                    state = loaded;
+                    front_buffer = composite_planes(&state);
+                    front_buffer_width = state.width;
+                }
+            }
+        }
 
         for (key, mapped) in key_map {
             set_key_state(&mut state, mapped, rl.is_key_down(key));
@@ -116,60 +139,48 @@ pub fn run_emulator_app(
             && cycles_run < max_cycles_per_frame
             && !state.exited
         {
-            // For CLS-framed ROMs (like snake), publish the completed frame right before
-            // the next clear starts the next frame.
-            if state.pc <= (MEMORY_SIZE - 2) {
-                let next_opcode =
-                    ((state.memory[state.pc] as u16) << 8) | state.memory[state.pc + 1] as u16;
-                if next_opcode == 0x00E0 && has_draw_since_clear {
-                    front_buffer = state.screen_buffer;
-                    has_draw_since_clear = false;
-                }
+            if !debugger.should_run_cycle(state.pc as u16) {
+                break;
             }
 
-            let pc_before = state.pc;
-            execute_cycle(&mut state, quirks)?;
-            if state.op == 0x00E0 {
-                frame_in_progress_after_clear = true;
-                has_draw_since_clear = false;
-            }
-            if (state.op & 0xF000) == 0xD000 {
-                if frame_in_progress_after_clear {
-                    has_draw_since_clear = true;
-                } else {
-                    // ROMs that don't use CLS still update smoothly.
-                    front_buffer = state.screen_buffer;
-                }
+            if let Err(error) = execute_cycle(&mut state, quirks) {
+                eprintln!("{}", format_post_mortem_trace(&state));
+                return Err(error);
             }
-            // If ROM blocks on LD Vx, K after drawing a frame (title screens),
-            // publish what we have even without a subsequent CLS boundary.
-            if (state.op & 0xF0FF) == 0xF00A && state.pc == pc_before && has_draw_since_clear {
-                front_buffer = state.screen_buffer;
-                has_draw_since_clear = false;
+            if debugger.paused {
+                debugger.print_dump(&state);
             }
             accumulated_time -= cycle_interval;
             cycles_run += 1;
         }
 
+        // The display only actually changes on a vblank, so publishing here
+        // (rather than guessing from CLS/draw opcode boundaries) is both
+        // simpler and matches the hardware this emulator models.
         while timer_accumulated_time >= timer_interval && !state.exited {
             tick_timers(&mut state, None);
+            front_buffer = composite_planes(&state);
+            front_buffer_width = state.width;
             timer_accumulated_time -= timer_interval;
         }
 
         let mut d = rl.begin_drawing(&thread);
         d.clear_background(Color::BLACK);
         for (index, value) in front_buffer.iter().enumerate() {
-            if *value == 0 {
-                continue;
-            }
-            let x = (index % SCREEN_WIDTH) as i32;
-            let y = (index / SCREEN_WIDTH) as i32;
+            let color = match value {
+                0 => continue,
+                1 => Color::WHITE,
+                2 => Color::new(100, 200, 255, 255),
+                _ => Color::new(255, 220, 120, 255),
+            };
+            let x = (index % front_buffer_width) as i32;
+            let y = (index / front_buffer_width) as i32;
             d.draw_rectangle(
                 x * scale as i32,
                 y * scale as i32,
                 scale as i32,
                 scale as i32,
-                Color::WHITE,
+                color,
             );
         }
         state.should_draw = false;
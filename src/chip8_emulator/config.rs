@@ -0,0 +1,62 @@
+pub const MEMORY_SIZE: usize = 4096;
+pub const PROGRAM_START: usize = 0x200;
+pub const REGISTER_COUNT: usize = 16;
+/// Maximum nested `CALL` depth, matching the 16-level stack of the original
+/// COSMAC VIP interpreter. `CALL` past this returns `Chip8Error::StackOverflow`
+/// instead of growing `stack` without bound.
+pub const STACK_DEPTH: usize = 16;
+pub const KEY_COUNT: usize = 16;
+
+/// Default CPU cycles run per 60 Hz timer tick by `CycleScheduler`, in the
+/// range real CHIP-8 interpreters ran at (roughly 600-700 Hz).
+pub const DEFAULT_CYCLES_PER_FRAME: usize = 11;
+pub const SCREEN_WIDTH: usize = 64;
+pub const SCREEN_HEIGHT: usize = 32;
+pub const HIRES_SCREEN_WIDTH: usize = 128;
+pub const HIRES_SCREEN_HEIGHT: usize = 64;
+
+/// 16 slots so both SCHIP (which only uses V0..V7) and XO-CHIP (V0..VF) fit
+/// the same array.
+pub const RPL_FLAG_COUNT: usize = 16;
+
+/// Number of independently-selectable XO-CHIP bit-planes.
+pub const PLANE_COUNT: usize = 2;
+
+/// Offset into `memory` where the large (Super-CHIP) hex-digit font is
+/// loaded, right after the regular small font.
+pub const LARGE_FONT_OFFSET: usize = FONT_BYTES.len();
+pub const LARGE_FONT_GLYPH_SIZE: usize = 10;
+
+pub const FONT_BYTES: [u8; 80] = [
+    0xF0, 0x90, 0x90, 0x90, 0xF0, // 0
+    0x20, 0x60, 0x20, 0x20, 0x70, // 1
+    0xF0, 0x10, 0xF0, 0x80, 0xF0, // 2
+    0xF0, 0x10, 0xF0, 0x10, 0xF0, // 3
+    0x90, 0x90, 0xF0, 0x10, 0x10, // 4
+    0xF0, 0x80, 0xF0, 0x10, 0xF0, // 5
+    0xF0, 0x80, 0xF0, 0x90, 0xF0, // 6
+    0xF0, 0x10, 0x20, 0x40, 0x40, // 7
+    0xF0, 0x90, 0xF0, 0x90, 0xF0, // 8
+    0xF0, 0x90, 0xF0, 0x10, 0xF0, // 9
+    0xF0, 0x90, 0xF0, 0x90, 0x90, // A
+    0xE0, 0x90, 0xE0, 0x90, 0xE0, // B
+    0xF0, 0x80, 0x80, 0x80, 0xF0, // C
+    0xE0, 0x90, 0x90, 0x90, 0xE0, // D
+    0xF0, 0x80, 0xF0, 0x80, 0xF0, // E
+    0xF0, 0x80, 0xF0, 0x80, 0x80, // F
+];
+
+/// Super-CHIP large hex-digit font, 10 bytes per glyph, digits 0-9 only.
+/// Indexed by `LARGE_FONT_OFFSET + digit * LARGE_FONT_GLYPH_SIZE`.
+pub const LARGE_FONT_BYTES: [u8; 100] = [
+    0x3C, 0x7E, 0xE7, 0xC3, 0xC3, 0xC3, 0xC3, 0xE7, 0x7E, 0x3C, // 0
+    0x18, 0x38, 0x58, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x3C, // 1
+    0x3E, 0x7F, 0xC3, 0x06, 0x0C, 0x18, 0x30, 0x60, 0xFF, 0xFF, // 2
+    0x3C, 0x7E, 0xC3, 0x03, 0x0E, 0x0E, 0x03, 0xC3, 0x7E, 0x3C, // 3
+    0x06, 0x0E, 0x1E, 0x36, 0x66, 0xC6, 0xFF, 0xFF, 0x06, 0x06, // 4
+    0xFF, 0xFF, 0xC0, 0xC0, 0xFC, 0xFE, 0x03, 0xC3, 0x7E, 0x3C, // 5
+    0x3E, 0x7C, 0xC0, 0xC0, 0xFC, 0xFE, 0xC3, 0xC3, 0x7E, 0x3C, // 6
+    0xFF, 0xFF, 0x03, 0x06, 0x0C, 0x18, 0x30, 0x60, 0x60, 0x60, // 7
+    0x3C, 0x7E, 0xC3, 0xC3, 0x7E, 0x7E, 0xC3, 0xC3, 0x7E, 0x3C, // 8
+    0x3C, 0x7E, 0xC3, 0xC3, 0x7F, 0x3F, 0x03, 0x03, 0x3E, 0x7C, // 9
+];
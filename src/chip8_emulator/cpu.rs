@@ -1,9 +1,12 @@
-use rand::random;
-
-use crate::chip8_emulator::config::{MEMORY_SIZE, SCREEN_HEIGHT, SCREEN_WIDTH};
+use crate::chip8_emulator::config::{
+    DEFAULT_CYCLES_PER_FRAME, LARGE_FONT_GLYPH_SIZE, LARGE_FONT_OFFSET, MEMORY_SIZE, PLANE_COUNT,
+    STACK_DEPTH,
+};
 use crate::chip8_emulator::error::Chip8Error;
+use crate::chip8_emulator::memory::Memory;
 use crate::chip8_emulator::quirks::Chip8Quirks;
-use crate::chip8_emulator::state::{clear_display, first_pressed_key, EmulatorState};
+use crate::chip8_emulator::rng::next_byte;
+use crate::chip8_emulator::state::{clear_display, first_pressed_key, set_hires, EmulatorState};
 
 fn x_register_index(opcode: u16) -> usize {
     ((opcode & 0x0F00) >> 8) as usize
@@ -26,16 +29,50 @@ fn nibble_n(opcode: u16) -> u8 {
 }
 
 pub fn execute_cycle(state: &mut EmulatorState, quirks: Chip8Quirks) -> Result<(), Chip8Error> {
+    if state.waiting_for_vblank {
+        return Ok(());
+    }
+
     if state.pc > (MEMORY_SIZE - 2) {
         return Err(Chip8Error::ProgramCounterOutOfBounds(state.pc));
     }
 
-    let opcode = ((state.memory[state.pc] as u16) << 8) | state.memory[state.pc + 1] as u16;
+    let opcode =
+        ((state.memory.read(state.pc)? as u16) << 8) | state.memory.read(state.pc + 1)? as u16;
+    state.push_pc_history(state.pc as u16, opcode);
     state.pc += 2;
 
     execute_opcode(state, opcode, quirks)
 }
 
+/// Runs `state` for up to `cycles` cycles (stopping early if it exits),
+/// without touching the delay/sound timers. Intended for tests and
+/// conformance harnesses that want a deterministic number of cycles rather
+/// than a frame cadence; `run_emulator_headless` is the counterpart that
+/// runs whole `CycleScheduler` frames and ticks the timers after each.
+///
+/// Under a `display_wait` profile (`ORIGINAL_QUIRKS`), `Dxyn`/`Dxy0` sets
+/// `state.waiting_for_vblank` and `execute_cycle` no-ops until it's cleared
+/// by a timer tick -- but this function never ticks timers, so without
+/// clearing the flag itself every remaining cycle would silently do
+/// nothing. Since there's no frame boundary to wait for here, each cycle is
+/// treated as its own vblank: the flag is cleared right after the cycle
+/// that set it, so the next cycle always makes progress.
+pub fn run_rom_until(
+    state: &mut EmulatorState,
+    quirks: Chip8Quirks,
+    cycles: usize,
+) -> Result<(), Chip8Error> {
+    for _ in 0..cycles {
+        if state.exited {
+            break;
+        }
+        execute_cycle(state, quirks)?;
+        state.waiting_for_vblank = false;
+    }
+    Ok(())
+}
+
 pub fn tick_timers(state: &mut EmulatorState, mut sound_callback: Option<&mut dyn FnMut()>) {
     state.delay_timer = state.delay_timer.saturating_sub(1);
 
@@ -45,6 +82,56 @@ pub fn tick_timers(state: &mut EmulatorState, mut sound_callback: Option<&mut dy
             callback();
         }
     }
+
+    state.waiting_for_vblank = false;
+}
+
+/// Runs a fixed number of CPU cycles per 60 Hz timer tick, so the timers
+/// always count down at the correct rate regardless of how fast the host
+/// front-end's wall clock drives cycles (`execute_cycle` itself never
+/// touches `delay_timer`/`sound_timer`). `cycles_per_frame` is the host's
+/// CPU-speed knob -- raise it to run a ROM faster, lower it to slow one
+/// down, all without changing the timer rate.
+pub struct CycleScheduler {
+    cycles_per_frame: usize,
+}
+
+impl CycleScheduler {
+    pub fn new(cycles_per_frame: usize) -> Self {
+        Self { cycles_per_frame }
+    }
+
+    pub fn cycles_per_frame(&self) -> usize {
+        self.cycles_per_frame
+    }
+
+    pub fn set_cycles_per_frame(&mut self, cycles_per_frame: usize) {
+        self.cycles_per_frame = cycles_per_frame;
+    }
+
+    /// Runs up to `cycles_per_frame` cycles (stopping early if `state`
+    /// exits) and then applies exactly one timer tick.
+    pub fn run_frame(
+        &self,
+        state: &mut EmulatorState,
+        quirks: Chip8Quirks,
+        sound_callback: Option<&mut dyn FnMut()>,
+    ) -> Result<(), Chip8Error> {
+        for _ in 0..self.cycles_per_frame {
+            if state.exited {
+                break;
+            }
+            execute_cycle(state, quirks)?;
+        }
+        tick_timers(state, sound_callback);
+        Ok(())
+    }
+}
+
+impl Default for CycleScheduler {
+    fn default() -> Self {
+        Self::new(DEFAULT_CYCLES_PER_FRAME)
+    }
 }
 
 pub fn execute_opcode(
@@ -55,12 +142,15 @@ pub fn execute_opcode(
     state.op = opcode;
 
     match opcode & 0xF000 {
-        0x0000 => handle_family_0(state, opcode),
+        0x0000 => handle_family_0(state, opcode, quirks),
         0x1000 => {
             state.pc = address_nnn(opcode);
             Ok(())
         }
         0x2000 => {
+            if state.stack.len() >= STACK_DEPTH {
+                return Err(Chip8Error::StackOverflow);
+            }
             state.stack.push(state.pc as u16);
             state.pc = address_nnn(opcode);
             Ok(())
@@ -103,7 +193,8 @@ pub fn execute_opcode(
             Ok(())
         }
         0xC000 => {
-            state.registers[x_register_index(opcode)] = random::<u8>() & byte_nn(opcode);
+            state.registers[x_register_index(opcode)] =
+                next_byte(&mut state.rng_state) & byte_nn(opcode);
             Ok(())
         }
         0xD000 => handle_opcode_dxyn_draw(state, opcode, quirks),
@@ -113,7 +204,15 @@ pub fn execute_opcode(
     }
 }
 
-fn handle_family_0(state: &mut EmulatorState, opcode: u16) -> Result<(), Chip8Error> {
+fn handle_family_0(
+    state: &mut EmulatorState,
+    opcode: u16,
+    quirks: Chip8Quirks,
+) -> Result<(), Chip8Error> {
+    if (opcode & 0xFFF0) == 0x00C0 {
+        return handle_opcode_00cn_scroll_down(state, opcode, quirks);
+    }
+
     match opcode {
         0x00E0 => {
             clear_display(state);
@@ -124,14 +223,102 @@ fn handle_family_0(state: &mut EmulatorState, opcode: u16) -> Result<(), Chip8Er
             state.pc = ret as usize;
             Ok(())
         }
+        0x00FB => scroll_columns(state, scroll_amount(state, 4, quirks) as isize, quirks),
+        0x00FC => scroll_columns(state, -(scroll_amount(state, 4, quirks) as isize), quirks),
         0x00FD => {
             state.exited = true;
             Ok(())
         }
+        0x00FE => {
+            set_hires(state, false);
+            Ok(())
+        }
+        0x00FF => {
+            set_hires(state, true);
+            Ok(())
+        }
         _ => Ok(()),
     }
 }
 
+/// Halves a Super-CHIP scroll amount in lo-res mode when the quirk is set,
+/// since lo-res pixels render double-size relative to hi-res.
+fn scroll_amount(state: &EmulatorState, amount: usize, quirks: Chip8Quirks) -> usize {
+    if !state.hires && quirks.scroll_amount_halved_in_lores {
+        usize::max(1, amount / 2)
+    } else {
+        amount
+    }
+}
+
+fn handle_opcode_00cn_scroll_down(
+    state: &mut EmulatorState,
+    opcode: u16,
+    quirks: Chip8Quirks,
+) -> Result<(), Chip8Error> {
+    let rows = scroll_amount(state, nibble_n(opcode) as usize, quirks);
+    let width = state.width;
+    let height = state.height;
+
+    if state.selected_planes & 0b01 != 0 {
+        scroll_down_buffer(&mut state.screen_buffer, width, height, rows);
+    }
+    if state.selected_planes & 0b10 != 0 {
+        scroll_down_buffer(&mut state.plane2_buffer, width, height, rows);
+    }
+
+    state.should_draw = true;
+    Ok(())
+}
+
+fn scroll_down_buffer(buffer: &mut [u8], width: usize, height: usize, rows: usize) {
+    for y in (0..height).rev() {
+        for x in 0..width {
+            buffer[x + y * width] = if y >= rows {
+                buffer[x + (y - rows) * width]
+            } else {
+                0
+            };
+        }
+    }
+}
+
+/// Shifts the display left/right by `columns` (negative = left), used by
+/// `00FB`/`00FC`.
+fn scroll_columns(
+    state: &mut EmulatorState,
+    columns: isize,
+    _quirks: Chip8Quirks,
+) -> Result<(), Chip8Error> {
+    let width = state.width;
+    let height = state.height;
+
+    if state.selected_planes & 0b01 != 0 {
+        state.screen_buffer = scroll_columns_buffer(&state.screen_buffer, width, height, columns);
+    }
+    if state.selected_planes & 0b10 != 0 {
+        state.plane2_buffer = scroll_columns_buffer(&state.plane2_buffer, width, height, columns);
+    }
+
+    state.should_draw = true;
+    Ok(())
+}
+
+fn scroll_columns_buffer(buffer: &[u8], width: usize, height: usize, columns: isize) -> Vec<u8> {
+    let mut shifted = vec![0u8; width * height];
+
+    for y in 0..height {
+        for x in 0..width {
+            let source_x = x as isize - columns;
+            if source_x >= 0 && (source_x as usize) < width {
+                shifted[x + y * width] = buffer[source_x as usize + y * width];
+            }
+        }
+    }
+
+    shifted
+}
+
 fn handle_opcode_5xy0_skip_eq_register(
     state: &mut EmulatorState,
     opcode: u16,
@@ -226,52 +413,183 @@ fn handle_opcode_dxyn_draw(
     opcode: u16,
     quirks: Chip8Quirks,
 ) -> Result<(), Chip8Error> {
-    let x_start = (state.registers[x_register_index(opcode)] as usize) % SCREEN_WIDTH;
-    let y_start = (state.registers[y_register_index(opcode)] as usize) % SCREEN_HEIGHT;
     let height = nibble_n(opcode) as usize;
+    if height == 0 {
+        return handle_opcode_dxy0_draw_16x16(state, opcode, quirks);
+    }
+
+    let x_start = (state.registers[x_register_index(opcode)] as usize) % state.width;
+    let y_start = (state.registers[y_register_index(opcode)] as usize) % state.height;
+    let width = state.width;
+    let draw_height = state.height;
+    let wrap = quirks.draw_wrap;
 
     let mut collision = 0;
+    let mut sprite_address = state.index;
+
+    for plane in 0..PLANE_COUNT {
+        if state.selected_planes & (1 << plane) == 0 {
+            continue;
+        }
+
+        let plane_collision = if plane == 0 {
+            xor_sprite_into_plane(
+                &mut state.screen_buffer,
+                width,
+                draw_height,
+                x_start,
+                y_start,
+                &state.memory,
+                sprite_address,
+                height,
+                1,
+                wrap,
+            )?
+        } else {
+            xor_sprite_into_plane(
+                &mut state.plane2_buffer,
+                width,
+                draw_height,
+                x_start,
+                y_start,
+                &state.memory,
+                sprite_address,
+                height,
+                1,
+                wrap,
+            )?
+        };
+        collision |= plane_collision;
+        sprite_address += height;
+    }
+
+    state.registers[0xF] = collision;
+    state.should_draw = true;
+    if quirks.display_wait {
+        state.waiting_for_vblank = true;
+    }
+
+    Ok(())
+}
+
+/// `Dxy0`: draws a Super-CHIP 16x16 sprite (32 bytes, two bytes per row)
+/// from `state.index`.
+fn handle_opcode_dxy0_draw_16x16(
+    state: &mut EmulatorState,
+    opcode: u16,
+    quirks: Chip8Quirks,
+) -> Result<(), Chip8Error> {
+    let x_start = (state.registers[x_register_index(opcode)] as usize) % state.width;
+    let y_start = (state.registers[y_register_index(opcode)] as usize) % state.height;
+    let width = state.width;
+    let draw_height = state.height;
+    let wrap = quirks.dxy0_wrap;
 
-    for row in 0..height {
+    let mut collision = 0;
+    let mut sprite_address = state.index;
+
+    for plane in 0..PLANE_COUNT {
+        if state.selected_planes & (1 << plane) == 0 {
+            continue;
+        }
+
+        let plane_collision = if plane == 0 {
+            xor_sprite_into_plane(
+                &mut state.screen_buffer,
+                width,
+                draw_height,
+                x_start,
+                y_start,
+                &state.memory,
+                sprite_address,
+                16,
+                2,
+                wrap,
+            )?
+        } else {
+            xor_sprite_into_plane(
+                &mut state.plane2_buffer,
+                width,
+                draw_height,
+                x_start,
+                y_start,
+                &state.memory,
+                sprite_address,
+                16,
+                2,
+                wrap,
+            )?
+        };
+        collision |= plane_collision;
+        sprite_address += 32;
+    }
+
+    state.registers[0xF] = collision;
+    state.should_draw = true;
+    if quirks.display_wait {
+        state.waiting_for_vblank = true;
+    }
+
+    Ok(())
+}
+
+/// XORs a sprite of `rows` rows (`bytes_per_row` bytes each, read from
+/// `memory` starting at `sprite_address`) into `buffer`, returning `1` if
+/// any set pixel was already on (collision). Shared by the 8-wide (`Dxyn`)
+/// and 16-wide (`Dxy0`) draws, and by each selected XO-CHIP plane.
+#[allow(clippy::too_many_arguments)]
+fn xor_sprite_into_plane<M: Memory>(
+    buffer: &mut [u8],
+    buffer_width: usize,
+    buffer_height: usize,
+    x_start: usize,
+    y_start: usize,
+    memory: &M,
+    sprite_address: usize,
+    rows: usize,
+    bytes_per_row: usize,
+    wrap: bool,
+) -> Result<u8, Chip8Error> {
+    let mut collision = 0;
+
+    for row in 0..rows {
         let mut y_pos = y_start + row;
-        if quirks.draw_wrap {
-            y_pos %= SCREEN_HEIGHT;
-        } else if y_pos >= SCREEN_HEIGHT {
+        if wrap {
+            y_pos %= buffer_height;
+        } else if y_pos >= buffer_height {
             break;
         }
 
-        let sprite_address = state.index + row;
-        if sprite_address >= MEMORY_SIZE {
-            return Err(Chip8Error::ProgramCounterOutOfBounds(sprite_address));
-        }
+        let row_address = sprite_address + row * bytes_per_row;
 
-        let sprite_row = state.memory[sprite_address];
+        let mut sprite_row: u32 = 0;
+        for byte_offset in 0..bytes_per_row {
+            sprite_row = (sprite_row << 8) | memory.read(row_address + byte_offset)? as u32;
+        }
+        let bit_count = bytes_per_row * 8;
 
-        for bit in 0..8 {
+        for bit in 0..bit_count {
             let mut x_pos = x_start + bit;
-            if quirks.draw_wrap {
-                x_pos %= SCREEN_WIDTH;
-            } else if x_pos >= SCREEN_WIDTH {
+            if wrap {
+                x_pos %= buffer_width;
+            } else if x_pos >= buffer_width {
                 break;
             }
 
-            let pixel = (sprite_row >> (7 - bit)) & 0x1;
+            let pixel = (sprite_row >> (bit_count - 1 - bit)) & 0x1;
             if pixel == 0 {
                 continue;
             }
 
-            let location = x_pos + (y_pos * SCREEN_WIDTH);
-            if state.screen_buffer[location] == 1 {
+            let location = x_pos + y_pos * buffer_width;
+            if buffer[location] == 1 {
                 collision = 1;
             }
-            state.screen_buffer[location] ^= 1;
+            buffer[location] ^= 1;
         }
     }
 
-    state.registers[0xF] = collision;
-    state.should_draw = true;
-
-    Ok(())
+    Ok(collision)
 }
 
 fn handle_family_e(state: &mut EmulatorState, opcode: u16) -> Result<(), Chip8Error> {
@@ -302,6 +620,12 @@ fn handle_family_f(
     let x_reg = x_register_index(opcode);
 
     match byte_nn(opcode) {
+        0x01 => {
+            // XO-CHIP plane select: the "x" nibble here is an immediate
+            // plane bitmask, not a register index.
+            state.selected_planes = x_reg as u8 & 0b11;
+            Ok(())
+        }
         0x07 => {
             state.registers[x_reg] = state.delay_timer;
             Ok(())
@@ -330,16 +654,21 @@ fn handle_family_f(
             state.index = ((state.registers[x_reg] & 0x0F) as usize) * 5;
             Ok(())
         }
+        0x30 => {
+            let digit = (state.registers[x_reg] & 0x0F).min(9) as usize;
+            state.index = LARGE_FONT_OFFSET + digit * LARGE_FONT_GLYPH_SIZE;
+            Ok(())
+        }
         0x33 => {
             let value = state.registers[x_reg];
-            state.memory[state.index] = value / 100;
-            state.memory[state.index + 1] = (value % 100) / 10;
-            state.memory[state.index + 2] = value % 10;
+            state.memory.write(state.index, value / 100)?;
+            state.memory.write(state.index + 1, (value % 100) / 10)?;
+            state.memory.write(state.index + 2, value % 10)?;
             Ok(())
         }
         0x55 => {
             for index in 0..=x_reg {
-                state.memory[state.index + index] = state.registers[index];
+                state.memory.write(state.index + index, state.registers[index])?;
             }
             if quirks.load_store_increment_i {
                 state.index = (state.index + x_reg + 1) & 0x0FFF;
@@ -348,13 +677,36 @@ fn handle_family_f(
         }
         0x65 => {
             for index in 0..=x_reg {
-                state.registers[index] = state.memory[state.index + index];
+                state.registers[index] = state.memory.read(state.index + index)?;
             }
             if quirks.load_store_increment_i {
                 state.index = (state.index + x_reg + 1) & 0x0FFF;
             }
             Ok(())
         }
+        0x75 => {
+            // XO-CHIP has 16 RPL flag registers; SCHIP (and plain CHIP-8) has 8.
+            let flag_count = if quirks.platform == crate::chip8_emulator::quirks::Platform::XoChip {
+                state.rpl_flags.len()
+            } else {
+                8
+            };
+            for index in 0..=x_reg.min(flag_count - 1) {
+                state.rpl_flags[index] = state.registers[index];
+            }
+            Ok(())
+        }
+        0x85 => {
+            let flag_count = if quirks.platform == crate::chip8_emulator::quirks::Platform::XoChip {
+                state.rpl_flags.len()
+            } else {
+                8
+            };
+            for index in 0..=x_reg.min(flag_count - 1) {
+                state.registers[index] = state.rpl_flags[index];
+            }
+            Ok(())
+        }
         _ => Err(Chip8Error::InvalidOpcode(opcode)),
     }
 }
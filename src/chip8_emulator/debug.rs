@@ -0,0 +1,283 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::assembler::{DebugMap, SourcePosition};
+use crate::chip8_emulator::cpu::execute_cycle;
+use crate::chip8_emulator::error::Chip8Error;
+use crate::chip8_emulator::quirks::Chip8Quirks;
+use crate::chip8_emulator::state::EmulatorState;
+use crate::disassembler::{disassemble_opcode, disassemble_range};
+
+/// How many cycles `continue_until_break` runs before giving up and
+/// returning control even without hitting a breakpoint/watchpoint.
+const CONTINUE_CYCLE_BUDGET: usize = 1_000_000;
+
+/// Pause/breakpoint/watchpoint/step state layered over `execute_cycle`.
+/// Runners consult this before executing a cycle instead of calling
+/// `execute_cycle` unconditionally; `step`/`continue_until_break` give a
+/// front-end REPL (see `execute_command`) a way to drive execution directly.
+#[derive(Debug, Default)]
+pub struct DebugController {
+    pub paused: bool,
+    pub step: bool,
+    pub step_counter: u64,
+    pub breakpoints: HashSet<u16>,
+    /// Memory addresses watched for writes. There's no write-interception
+    /// hook in `cpu.rs`, so a watchpoint is detected by diffing a snapshot
+    /// taken before the cycle against `state.memory` after it.
+    pub watchpoints: HashSet<u16>,
+    watch_snapshot: HashMap<u16, u8>,
+    /// Assembled-source line map, attached via `set_source_map` when the
+    /// loaded ROM came from `assemble_text_with_debug`/`assemble_file_with_debug`.
+    /// Lets `current_line` answer "what source line is `pc` executing?".
+    source_map: Option<DebugMap>,
+}
+
+impl DebugController {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_source_map(&mut self, source_map: DebugMap) {
+        self.source_map = Some(source_map);
+    }
+
+    /// The source line currently executing at `pc`, if a debug map was
+    /// attached via `set_source_map`. A future step-debugger front-end can
+    /// use this to show "currently executing line N" next to the disassembly.
+    pub fn current_line(&self, pc: u16) -> Option<SourcePosition> {
+        self.source_map
+            .as_ref()?
+            .lookup(pc as usize)
+            .map(|entry| entry.position)
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    pub fn request_step(&mut self) {
+        self.step = true;
+    }
+
+    pub fn add_breakpoint(&mut self, address: u16) {
+        self.breakpoints.insert(address);
+    }
+
+    pub fn remove_breakpoint(&mut self, address: u16) {
+        self.breakpoints.remove(&address);
+    }
+
+    pub fn add_watchpoint(&mut self, address: u16) {
+        self.watchpoints.insert(address);
+    }
+
+    pub fn remove_watchpoint(&mut self, address: u16) {
+        self.watchpoints.remove(&address);
+    }
+
+    /// Called before each `execute_cycle`. Returns `true` if a cycle should
+    /// run this call. Flips to paused when `pc` hits a
+    /// breakpoint.
+    pub fn should_run_cycle(&mut self, pc: u16) -> bool {
+        if self.breakpoints.contains(&pc) {
+            self.paused = true;
+        }
+
+        if !self.paused {
+            return true;
+        }
+
+        if self.step {
+            self.step = false;
+            self.step_counter += 1;
+            return true;
+        }
+
+        false
+    }
+
+    /// Runs exactly one cycle, unconditionally, for a `step` debugger
+    /// command. Pauses afterwards if the new `pc` is a breakpoint or a
+    /// watched address changed.
+    pub fn step(&mut self, state: &mut EmulatorState, quirks: Chip8Quirks) -> Result<(), Chip8Error> {
+        self.snapshot_watchpoints(state);
+        execute_cycle(state, quirks)?;
+        self.step_counter += 1;
+
+        self.check_watchpoints(state);
+        if self.breakpoints.contains(&(state.pc as u16)) {
+            self.paused = true;
+        }
+
+        Ok(())
+    }
+
+    /// Runs cycles until a breakpoint/watchpoint pauses execution, the ROM
+    /// exits, or `CONTINUE_CYCLE_BUDGET` cycles have run.
+    pub fn continue_until_break(
+        &mut self,
+        state: &mut EmulatorState,
+        quirks: Chip8Quirks,
+    ) -> Result<(), Chip8Error> {
+        self.paused = false;
+
+        for _ in 0..CONTINUE_CYCLE_BUDGET {
+            if state.exited {
+                break;
+            }
+
+            self.step(state, quirks)?;
+
+            if self.paused {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn snapshot_watchpoints(&mut self, state: &EmulatorState) {
+        self.watch_snapshot.clear();
+        for &address in &self.watchpoints {
+            if let Some(byte) = state.memory.get(address as usize) {
+                self.watch_snapshot.insert(address, *byte);
+            }
+        }
+    }
+
+    fn check_watchpoints(&mut self, state: &EmulatorState) {
+        for (&address, previous) in &self.watch_snapshot {
+            if state.memory.get(address as usize) != Some(previous) {
+                self.paused = true;
+            }
+        }
+    }
+
+    /// Parses and runs a single debugger command, returning the text a REPL
+    /// should print. Recognized commands: `regs`, `pc`, `stack`,
+    /// `mem <addr> <len>`, `disasm <addr> <len>`, `break <addr>`,
+    /// `clear <addr>`, `watch <addr>`, `unwatch <addr>`, `step [n]`,
+    /// `continue`. Addresses and lengths are hex, with or without a `0x`
+    /// prefix; `step`'s `n` (default 1) repeats the step that many times,
+    /// stopping early if a breakpoint/watchpoint pauses execution.
+    pub fn execute_command(
+        &mut self,
+        command: &str,
+        state: &mut EmulatorState,
+        quirks: Chip8Quirks,
+    ) -> Result<String, Chip8Error> {
+        let mut parts = command.split_whitespace();
+        let name = parts.next().unwrap_or("");
+
+        match name {
+            "regs" => Ok(format_registers(state)),
+            "pc" => Ok(format!("pc=0x{:03x} index=0x{:03x}", state.pc, state.index)),
+            "stack" => Ok(format!("{:04x?}", state.stack)),
+            "mem" => {
+                let addr = parse_hex(parts.next())?.min(state.memory.len());
+                let len = parse_hex(parts.next())?;
+                let end = (addr + len).min(state.memory.len());
+                Ok(format!("{:02x?}", &state.memory[addr..end]))
+            }
+            "disasm" => {
+                let addr = parse_hex(parts.next())?;
+                let len = parse_hex(parts.next())?;
+                disassemble_range(&state.memory, addr, addr + len)
+                    .map_err(|_| Chip8Error::InvalidArgument("invalid disassembly range"))
+            }
+            "break" => {
+                self.add_breakpoint(parse_hex(parts.next())? as u16);
+                Ok("breakpoint set".to_owned())
+            }
+            "clear" => {
+                self.remove_breakpoint(parse_hex(parts.next())? as u16);
+                Ok("breakpoint cleared".to_owned())
+            }
+            "watch" => {
+                self.add_watchpoint(parse_hex(parts.next())? as u16);
+                Ok("watchpoint set".to_owned())
+            }
+            "unwatch" => {
+                self.remove_watchpoint(parse_hex(parts.next())? as u16);
+                Ok("watchpoint cleared".to_owned())
+            }
+            "step" => {
+                let repeat = match parts.next() {
+                    Some(text) => parse_hex(Some(text))?,
+                    None => 1,
+                };
+                let was_paused = self.paused;
+                self.paused = false;
+                for remaining in (0..repeat).rev() {
+                    if state.exited {
+                        break;
+                    }
+                    self.step(state, quirks)?;
+                    if self.paused && remaining > 0 {
+                        break;
+                    }
+                }
+                self.paused |= was_paused;
+                Ok(format!("stepped to pc=0x{:03x}", state.pc))
+            }
+            "continue" => {
+                self.continue_until_break(state, quirks)?;
+                Ok(format!("stopped at pc=0x{:03x}", state.pc))
+            }
+            _ => Err(Chip8Error::InvalidArgument("unknown debugger command")),
+        }
+    }
+
+    /// Prints the decoded current instruction, the 16 registers, `index`,
+    /// the call stack, the delay/sound timers, and the recent PC history,
+    /// for use while `paused` is true.
+    pub fn print_dump(&self, state: &EmulatorState) {
+        println!("-- paused at pc=0x{:03x} (step {}) --", state.pc, self.step_counter);
+
+        if let (Some(&high), Some(&low)) = (state.memory.get(state.pc), state.memory.get(state.pc + 1)) {
+            let opcode = ((high as u16) << 8) | low as u16;
+            println!("0x{:03x}: {}", state.pc, disassemble_opcode(opcode));
+        }
+
+        print!("pc history:");
+        for (pc, opcode) in state.recent_pc_history() {
+            print!(" 0x{pc:03x}:0x{opcode:04x}");
+        }
+        println!();
+
+        println!("{}", format_registers(state));
+        println!(
+            "index=0x{:03x} delay_timer={} sound_timer={}",
+            state.index, state.delay_timer, state.sound_timer
+        );
+        println!("stack: {:04x?}", state.stack);
+    }
+}
+
+/// Formats the `(pc, opcode)` ring buffer for a post-mortem dump when
+/// `execute_cycle` raises a `Chip8Error`. Unused entries (before the buffer
+/// has wrapped once) are zeroed and skipped.
+pub fn format_post_mortem_trace(state: &EmulatorState) -> String {
+    let mut text = String::from("post-mortem trace (oldest first):");
+    for (pc, opcode) in state.recent_pc_history() {
+        if pc == 0 && opcode == 0 {
+            continue;
+        }
+        text.push_str(&format!(" 0x{pc:03x}:0x{opcode:04x}"));
+    }
+    text
+}
+
+fn format_registers(state: &EmulatorState) -> String {
+    let mut text = String::new();
+    for (index, value) in state.registers.iter().enumerate() {
+        text.push_str(&format!("v{index:X}=0x{value:02x} "));
+    }
+    text
+}
+
+fn parse_hex(text: Option<&str>) -> Result<usize, Chip8Error> {
+    let text = text.ok_or(Chip8Error::InvalidArgument("missing address/length argument"))?;
+    usize::from_str_radix(text.trim_start_matches("0x"), 16)
+        .map_err(|_| Chip8Error::InvalidArgument("expected a hex number"))
+}
@@ -1,13 +1,18 @@
 use std::fmt::{Display, Formatter};
 
+use crate::chip8_emulator::config::STACK_DEPTH;
+
 #[derive(Debug)]
 pub enum Chip8Error {
     Io(std::io::Error),
     RomTooLarge { size: usize, max: usize },
     ProgramCounterOutOfBounds(usize),
+    MemoryOutOfBounds(usize),
     InvalidOpcode(u16),
+    StackOverflow,
     StackUnderflow,
     InvalidArgument(&'static str),
+    UnsupportedSaveVersion { found: u8, expected: u8 },
 }
 
 impl Display for Chip8Error {
@@ -20,9 +25,17 @@ impl Display for Chip8Error {
             Self::ProgramCounterOutOfBounds(pc) => {
                 write!(f, "program counter exceeded program memory: 0x{pc:03x}")
             }
+            Self::MemoryOutOfBounds(address) => {
+                write!(f, "memory access out of bounds: 0x{address:03x}")
+            }
             Self::InvalidOpcode(opcode) => write!(f, "invalid opcode: 0x{opcode:04x}"),
+            Self::StackOverflow => write!(f, "call stack exceeded depth {STACK_DEPTH}"),
             Self::StackUnderflow => write!(f, "return instruction with empty stack"),
             Self::InvalidArgument(argument) => write!(f, "invalid argument: {argument}"),
+            Self::UnsupportedSaveVersion { found, expected } => write!(
+                f,
+                "unsupported save file version {found} (expected {expected})"
+            ),
         }
     }
 }
@@ -0,0 +1,27 @@
+use crate::chip8_emulator::error::Chip8Error;
+
+/// Bounds-checked byte-addressable memory. Opcode handlers that go through
+/// this trait (the `execute_cycle` fetch, the `Dxyn`/`Dxy0` draw handlers,
+/// and `FX33`/`FX55`/`FX65`) get a `Chip8Error` on an out-of-range address
+/// instead of a panic, and can be backed by something other than the fixed
+/// 4 KB RAM array -- a larger XO-CHIP address space, say, or a
+/// memory-mapped I/O region -- without the handlers changing at all.
+pub trait Memory {
+    fn read(&self, address: usize) -> Result<u8, Chip8Error>;
+    fn write(&mut self, address: usize, value: u8) -> Result<(), Chip8Error>;
+}
+
+impl<const N: usize> Memory for [u8; N] {
+    fn read(&self, address: usize) -> Result<u8, Chip8Error> {
+        self.get(address)
+            .copied()
+            .ok_or(Chip8Error::MemoryOutOfBounds(address))
+    }
+
+    fn write(&mut self, address: usize, value: u8) -> Result<(), Chip8Error> {
+        *self
+            .get_mut(address)
+            .ok_or(Chip8Error::MemoryOutOfBounds(address))? = value;
+        Ok(())
+    }
+}
@@ -0,0 +1,12 @@
+pub mod app;
+pub mod config;
+pub mod cpu;
+pub mod debug;
+pub mod error;
+pub mod memory;
+pub mod quirks;
+pub mod rewind;
+pub mod rng;
+pub mod save_state;
+pub mod state;
+pub mod terminal;
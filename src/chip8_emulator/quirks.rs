@@ -1,33 +1,89 @@
 use std::env;
 
+/// Which machine's opcode repertoire and resource limits a profile targets.
+/// Every extended opcode (SCHIP scrolls/hi-res/Dxy0, XO-CHIP plane select)
+/// is always dispatched regardless of `platform` -- this field only steers
+/// profile selection and things like RPL flag register counts, not opcode
+/// availability.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+    Chip8,
+    SuperChip,
+    XoChip,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Chip8Quirks {
+    pub platform: Platform,
     pub shift_uses_vy: bool,
     pub load_store_increment_i: bool,
     pub jump_with_vx: bool,
     pub draw_wrap: bool,
+    /// In lo-res Super-CHIP mode, `00Cn`/`00FB`/`00FC` scroll by half the
+    /// hi-res pixel count, since lo-res pixels are rendered double-size.
+    pub scroll_amount_halved_in_lores: bool,
+    /// Whether the 16x16 `Dxy0` sprite wraps at screen edges instead of
+    /// clipping.
+    pub dxy0_wrap: bool,
+    /// COSMAC VIP display-wait: `Dxyn`/`Dxy0` block further execution until
+    /// the next 60 Hz timer tick, modeling the original vblank interrupt.
+    pub display_wait: bool,
 }
 
 pub const ORIGINAL_QUIRKS: Chip8Quirks = Chip8Quirks {
+    platform: Platform::Chip8,
     shift_uses_vy: true,
     load_store_increment_i: true,
     jump_with_vx: false,
     draw_wrap: false,
+    scroll_amount_halved_in_lores: false,
+    dxy0_wrap: false,
+    display_wait: true,
 };
 
 pub const MODERN_QUIRKS: Chip8Quirks = Chip8Quirks {
+    platform: Platform::Chip8,
     shift_uses_vy: false,
     load_store_increment_i: false,
     jump_with_vx: true,
     draw_wrap: true,
+    scroll_amount_halved_in_lores: false,
+    dxy0_wrap: true,
+    display_wait: false,
+};
+
+pub const SCHIP_QUIRKS: Chip8Quirks = Chip8Quirks {
+    platform: Platform::SuperChip,
+    shift_uses_vy: false,
+    load_store_increment_i: false,
+    jump_with_vx: true,
+    draw_wrap: false,
+    scroll_amount_halved_in_lores: true,
+    dxy0_wrap: false,
+    display_wait: false,
+};
+
+/// XO-CHIP builds on the Super-CHIP quirk set, adding the wider RPL flag
+/// array and bit-planed display (see `config::RPL_FLAG_COUNT`/`PLANE_COUNT`
+/// and the `0xFN01` plane-select opcode).
+pub const XOCHIP_QUIRKS: Chip8Quirks = Chip8Quirks {
+    platform: Platform::XoChip,
+    ..SCHIP_QUIRKS
 };
 
 pub fn load_quirks_profile(profile: &str) -> Result<Chip8Quirks, String> {
     match profile.trim().to_ascii_lowercase().as_str() {
-        "original" => Ok(ORIGINAL_QUIRKS),
-        "modern" => Ok(MODERN_QUIRKS),
+        // `cosmac_vip`/`chip48`/`superchip` are the names these profiles are
+        // commonly known by elsewhere; accepted as aliases for the platform
+        // names above so a ROM's documented "runs best on X" recommendation
+        // can be used verbatim.
+        "original" | "cosmac_vip" => Ok(ORIGINAL_QUIRKS),
+        "modern" | "chip48" => Ok(MODERN_QUIRKS),
+        "schip" | "superchip" => Ok(SCHIP_QUIRKS),
+        "xochip" => Ok(XOCHIP_QUIRKS),
         other => Err(format!(
-            "invalid CHIP8_QUIRKS '{other}', expected one of: modern, original"
+            "invalid CHIP8_QUIRKS '{other}', expected one of: modern, original, schip, xochip, \
+             cosmac_vip, chip48, superchip"
         )),
     }
 }
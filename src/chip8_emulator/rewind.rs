@@ -0,0 +1,66 @@
+use std::collections::VecDeque;
+
+use crate::chip8_emulator::state::EmulatorState;
+
+/// Captures an `EmulatorState::snapshot()` every `interval` cycles, keeping
+/// at most `capacity` of them, so a user (or the debugger) can step
+/// backward through execution. Oldest snapshots are dropped once `capacity`
+/// is reached.
+#[derive(Debug)]
+pub struct RewindBuffer {
+    interval: u64,
+    capacity: usize,
+    cycles_since_capture: u64,
+    snapshots: VecDeque<Vec<u8>>,
+}
+
+impl RewindBuffer {
+    pub fn new(interval: u64, capacity: usize) -> Self {
+        Self {
+            interval: interval.max(1),
+            capacity: capacity.max(1),
+            cycles_since_capture: 0,
+            snapshots: VecDeque::new(),
+        }
+    }
+
+    /// Called once per executed cycle. Captures a snapshot every `interval`
+    /// cycles.
+    pub fn record_cycle(&mut self, state: &EmulatorState) {
+        self.cycles_since_capture += 1;
+        if self.cycles_since_capture < self.interval {
+            return;
+        }
+        self.cycles_since_capture = 0;
+
+        if self.snapshots.len() == self.capacity {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back(state.snapshot());
+    }
+
+    /// Pops and restores the most recent snapshot, stepping `state` one
+    /// capture point backward. Returns `false` (leaving `state` untouched)
+    /// if there's nothing left to rewind to.
+    pub fn rewind(&mut self, state: &mut EmulatorState) -> bool {
+        let Some(bytes) = self.snapshots.pop_back() else {
+            return false;
+        };
+
+        match EmulatorState::restore(&bytes) {
+            Ok(restored) => {
+                *state = restored;
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+}
@@ -0,0 +1,24 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// SplitMix64: a small, fast, seedable PRNG. Not cryptographically secure,
+/// but reproducible given the same seed, which is all `Cxnn` needs.
+pub fn next_u64(rng_state: &mut u64) -> u64 {
+    *rng_state = rng_state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *rng_state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Draws a single random byte, advancing `rng_state`.
+pub fn next_byte(rng_state: &mut u64) -> u8 {
+    (next_u64(rng_state) & 0xFF) as u8
+}
+
+/// Seeds from the wall clock when the caller doesn't supply a fixed seed.
+pub fn seed_from_entropy() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos() as u64)
+        .unwrap_or(0x2545_F491_4F6C_DD1D)
+}
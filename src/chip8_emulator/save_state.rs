@@ -0,0 +1,23 @@
+use std::fs;
+use std::path::Path;
+
+use crate::chip8_emulator::error::Chip8Error;
+use crate::chip8_emulator::state::EmulatorState;
+
+/// Quick-save/quick-load hotkeys derive their path from the loaded ROM so a
+/// snapshot sits next to the ROM it was taken from.
+pub fn quick_save_path(rom_path: &Path) -> std::path::PathBuf {
+    rom_path.with_extension("sav")
+}
+
+/// Writes `state.snapshot()` to `path`.
+pub fn save_state(state: &EmulatorState, path: &Path) -> Result<(), Chip8Error> {
+    fs::write(path, state.snapshot())?;
+    Ok(())
+}
+
+/// Reads `path` and restores an `EmulatorState` via `EmulatorState::restore`.
+pub fn load_state(path: &Path) -> Result<EmulatorState, Chip8Error> {
+    let bytes = fs::read(path)?;
+    EmulatorState::restore(&bytes)
+}
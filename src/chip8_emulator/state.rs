@@ -2,9 +2,14 @@ use std::fs;
 use std::path::{Path, PathBuf};
 
 use crate::chip8_emulator::config::{
-    FONT_BYTES, KEY_COUNT, MEMORY_SIZE, PROGRAM_START, REGISTER_COUNT, SCREEN_HEIGHT, SCREEN_WIDTH,
+    FONT_BYTES, HIRES_SCREEN_HEIGHT, HIRES_SCREEN_WIDTH, KEY_COUNT, LARGE_FONT_BYTES,
+    LARGE_FONT_OFFSET, MEMORY_SIZE, PROGRAM_START, REGISTER_COUNT, RPL_FLAG_COUNT, SCREEN_HEIGHT,
+    SCREEN_WIDTH,
 };
 use crate::chip8_emulator::error::Chip8Error;
+use crate::chip8_emulator::rng;
+
+pub const PC_HISTORY_SIZE: usize = 512;
 
 #[derive(Debug, Clone)]
 pub struct EmulatorState {
@@ -12,7 +17,18 @@ pub struct EmulatorState {
     pub registers: [u8; REGISTER_COUNT],
     pub stack: Vec<u16>,
     pub key_inputs: [u8; KEY_COUNT],
-    pub screen_buffer: [u8; SCREEN_WIDTH * SCREEN_HEIGHT],
+    pub screen_buffer: Vec<u8>,
+    /// XO-CHIP's second bit-plane. Unused (and always zero) outside XO-CHIP
+    /// ROMs, since `selected_planes` defaults to plane 0 only.
+    pub plane2_buffer: Vec<u8>,
+    /// Bitmask of bit-planes `Dxyn`/`Dxy0`, `00E0`, and the scroll opcodes
+    /// act on: bit 0 is `screen_buffer`, bit 1 is `plane2_buffer`. Set by
+    /// `0xFN01`; defaults to plane 0 only.
+    pub selected_planes: u8,
+    pub width: usize,
+    pub height: usize,
+    pub hires: bool,
+    pub rpl_flags: [u8; RPL_FLAG_COUNT],
     pub pc: usize,
     pub index: usize,
     pub delay_timer: u8,
@@ -21,6 +37,14 @@ pub struct EmulatorState {
     pub exited: bool,
     pub op: u16,
     pub rom_path: Option<PathBuf>,
+    /// Ring buffer of the last `(pc, opcode)` pairs executed, for a
+    /// post-mortem trace when a `Chip8Error` is raised.
+    pub pc_history: [(u16, u16); PC_HISTORY_SIZE],
+    pub pc_history_head: usize,
+    pub rng_state: u64,
+    /// Set by `Dxyn`/`Dxy0` under the `display_wait` quirk; cleared by the
+    /// next `tick_timers` call. While set, `execute_cycle` refuses to fetch.
+    pub waiting_for_vblank: bool,
 }
 
 impl Default for EmulatorState {
@@ -30,7 +54,13 @@ impl Default for EmulatorState {
             registers: [0; REGISTER_COUNT],
             stack: Vec::new(),
             key_inputs: [0; KEY_COUNT],
-            screen_buffer: [0; SCREEN_WIDTH * SCREEN_HEIGHT],
+            screen_buffer: vec![0; SCREEN_WIDTH * SCREEN_HEIGHT],
+            plane2_buffer: vec![0; SCREEN_WIDTH * SCREEN_HEIGHT],
+            selected_planes: 0b01,
+            width: SCREEN_WIDTH,
+            height: SCREEN_HEIGHT,
+            hires: false,
+            rpl_flags: [0; RPL_FLAG_COUNT],
             pc: PROGRAM_START,
             index: 0,
             delay_timer: 0,
@@ -39,21 +69,255 @@ impl Default for EmulatorState {
             exited: false,
             op: 0,
             rom_path: None,
+            pc_history: [(0, 0); PC_HISTORY_SIZE],
+            pc_history_head: 0,
+            rng_state: rng::seed_from_entropy(),
+            waiting_for_vblank: false,
+        }
+    }
+}
+
+impl EmulatorState {
+    /// Records an executed instruction's address and opcode into the ring
+    /// buffer.
+    pub fn push_pc_history(&mut self, pc: u16, opcode: u16) {
+        self.pc_history[self.pc_history_head] = (pc, opcode);
+        self.pc_history_head = (self.pc_history_head + 1) % PC_HISTORY_SIZE;
+    }
+
+    /// Returns the recorded `(pc, opcode)` pairs in execution order, oldest
+    /// first.
+    pub fn recent_pc_history(&self) -> Vec<(u16, u16)> {
+        let mut history = Vec::with_capacity(PC_HISTORY_SIZE);
+        for offset in 0..PC_HISTORY_SIZE {
+            let index = (self.pc_history_head + offset) % PC_HISTORY_SIZE;
+            history.push(self.pc_history[index]);
+        }
+        history
+    }
+
+    /// Serializes a full snapshot of this state to a compact binary blob: a
+    /// magic header, a version byte (bumped on format changes), then the
+    /// machine state in a fixed field order. Debug-only bookkeeping
+    /// (`pc_history`) is intentionally left out since it carries no
+    /// gameplay meaning. Used directly by `save_state`/`load_state` and by
+    /// `RewindBuffer` for in-memory rewind points.
+    pub fn snapshot(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&SNAPSHOT_MAGIC);
+        bytes.push(SNAPSHOT_VERSION);
+
+        bytes.extend_from_slice(&self.memory);
+        bytes.extend_from_slice(&self.registers);
+
+        write_u16(&mut bytes, self.stack.len() as u16);
+        for value in &self.stack {
+            write_u16(&mut bytes, *value);
+        }
+
+        bytes.extend_from_slice(&self.key_inputs);
+
+        write_u32(&mut bytes, self.width as u32);
+        write_u32(&mut bytes, self.height as u32);
+        bytes.push(u8::from(self.hires));
+        bytes.extend_from_slice(&self.screen_buffer);
+        bytes.extend_from_slice(&self.plane2_buffer);
+        bytes.push(self.selected_planes);
+
+        bytes.extend_from_slice(&self.rpl_flags);
+
+        write_u32(&mut bytes, self.pc as u32);
+        write_u32(&mut bytes, self.index as u32);
+        bytes.push(self.delay_timer);
+        bytes.push(self.sound_timer);
+        bytes.push(u8::from(self.exited));
+        write_u16(&mut bytes, self.op);
+        write_u64(&mut bytes, self.rng_state);
+        bytes.push(u8::from(self.waiting_for_vblank));
+
+        match &self.rom_path {
+            Some(rom_path) => {
+                let path_text = rom_path.to_string_lossy();
+                bytes.push(1);
+                write_u16(&mut bytes, path_text.len() as u16);
+                bytes.extend_from_slice(path_text.as_bytes());
+            }
+            None => bytes.push(0),
         }
+
+        bytes
+    }
+
+    /// Restores an `EmulatorState` previously produced by `snapshot`.
+    pub fn restore(bytes: &[u8]) -> Result<EmulatorState, Chip8Error> {
+        let mut reader = SnapshotReader::new(bytes);
+
+        let magic = reader.read_bytes(4)?;
+        if magic != SNAPSHOT_MAGIC.as_slice() {
+            return Err(Chip8Error::UnsupportedSaveVersion {
+                found: 0,
+                expected: SNAPSHOT_VERSION,
+            });
+        }
+
+        let version = reader.read_u8()?;
+        if version != SNAPSHOT_VERSION {
+            return Err(Chip8Error::UnsupportedSaveVersion {
+                found: version,
+                expected: SNAPSHOT_VERSION,
+            });
+        }
+
+        let memory = reader.read_array::<MEMORY_SIZE>()?;
+        let registers = reader.read_array::<REGISTER_COUNT>()?;
+
+        let stack_len = reader.read_u16()? as usize;
+        let mut stack = Vec::with_capacity(stack_len);
+        for _ in 0..stack_len {
+            stack.push(reader.read_u16()?);
+        }
+
+        let key_inputs = reader.read_array::<KEY_COUNT>()?;
+
+        let width = reader.read_u32()? as usize;
+        let height = reader.read_u32()? as usize;
+        let hires = reader.read_u8()? != 0;
+        let screen_buffer = reader.read_bytes(width * height)?.to_vec();
+        let plane2_buffer = reader.read_bytes(width * height)?.to_vec();
+        let selected_planes = reader.read_u8()?;
+
+        let rpl_flags = reader.read_array::<RPL_FLAG_COUNT>()?;
+
+        let pc = reader.read_u32()? as usize;
+        let index = reader.read_u32()? as usize;
+        let delay_timer = reader.read_u8()?;
+        let sound_timer = reader.read_u8()?;
+        let exited = reader.read_u8()? != 0;
+        let op = reader.read_u16()?;
+        let rng_state = reader.read_u64()?;
+        let waiting_for_vblank = reader.read_u8()? != 0;
+
+        let rom_path = if reader.read_u8()? != 0 {
+            let path_len = reader.read_u16()? as usize;
+            let path_text = std::str::from_utf8(reader.read_bytes(path_len)?)
+                .map_err(|_| Chip8Error::InvalidArgument("snapshot has non-UTF8 rom_path"))?;
+            Some(PathBuf::from(path_text))
+        } else {
+            None
+        };
+
+        Ok(EmulatorState {
+            memory,
+            registers,
+            stack,
+            key_inputs,
+            screen_buffer,
+            plane2_buffer,
+            selected_planes,
+            width,
+            height,
+            hires,
+            rpl_flags,
+            pc,
+            index,
+            delay_timer,
+            sound_timer,
+            should_draw: true,
+            exited,
+            op,
+            rom_path,
+            pc_history: [(0, 0); PC_HISTORY_SIZE],
+            pc_history_head: 0,
+            rng_state,
+            waiting_for_vblank,
+        })
     }
 }
 
-pub fn create_state(rom_path: Option<&Path>) -> Result<EmulatorState, Chip8Error> {
+const SNAPSHOT_MAGIC: [u8; 4] = *b"C8SV";
+const SNAPSHOT_VERSION: u8 = 2;
+
+fn write_u16(buffer: &mut Vec<u8>, value: u16) {
+    buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+fn write_u32(buffer: &mut Vec<u8>, value: u32) {
+    buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+fn write_u64(buffer: &mut Vec<u8>, value: u64) {
+    buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+struct SnapshotReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> SnapshotReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], Chip8Error> {
+        let end = self.pos + len;
+        let slice = self
+            .data
+            .get(self.pos..end)
+            .ok_or(Chip8Error::InvalidArgument("snapshot is truncated"))?;
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N], Chip8Error> {
+        Ok(self.read_bytes(N)?.try_into().unwrap())
+    }
+
+    fn read_u8(&mut self) -> Result<u8, Chip8Error> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    fn read_u16(&mut self) -> Result<u16, Chip8Error> {
+        let bytes = self.read_bytes(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn read_u32(&mut self) -> Result<u32, Chip8Error> {
+        let bytes = self.read_bytes(4)?;
+        Ok(u32::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    fn read_u64(&mut self) -> Result<u64, Chip8Error> {
+        let bytes = self.read_bytes(8)?;
+        Ok(u64::from_le_bytes(bytes.try_into().unwrap()))
+    }
+}
+
+pub fn create_state(
+    rom_path: Option<&Path>,
+    seed: Option<u64>,
+) -> Result<EmulatorState, Chip8Error> {
     let mut state = EmulatorState::default();
-    reset_state(&mut state, rom_path)?;
+    reset_state(&mut state, rom_path, seed)?;
     Ok(state)
 }
 
-pub fn reset_state(state: &mut EmulatorState, rom_path: Option<&Path>) -> Result<(), Chip8Error> {
+/// Resets `state` to a fresh boot. `seed` fixes the `Cxnn` PRNG for
+/// reproducible runs; `None` reseeds from wall-clock entropy.
+pub fn reset_state(
+    state: &mut EmulatorState,
+    rom_path: Option<&Path>,
+    seed: Option<u64>,
+) -> Result<(), Chip8Error> {
     state.memory = [0; MEMORY_SIZE];
     state.registers = [0; REGISTER_COUNT];
     state.stack.clear();
     state.key_inputs = [0; KEY_COUNT];
+    state.width = SCREEN_WIDTH;
+    state.height = SCREEN_HEIGHT;
+    state.hires = false;
+    state.rpl_flags = [0; RPL_FLAG_COUNT];
+    state.selected_planes = 0b01;
     clear_display(state);
 
     state.pc = PROGRAM_START;
@@ -62,6 +326,10 @@ pub fn reset_state(state: &mut EmulatorState, rom_path: Option<&Path>) -> Result
     state.sound_timer = 0;
     state.exited = false;
     state.op = 0;
+    state.pc_history = [(0, 0); PC_HISTORY_SIZE];
+    state.pc_history_head = 0;
+    state.rng_state = seed.unwrap_or_else(rng::seed_from_entropy);
+    state.waiting_for_vblank = false;
 
     load_font(state);
 
@@ -76,13 +344,48 @@ pub fn reset_state(state: &mut EmulatorState, rom_path: Option<&Path>) -> Result
     Ok(())
 }
 
+/// Clears the selected bit-planes (see `EmulatorState::selected_planes`).
+/// Also resizes either buffer that doesn't match the current resolution, so
+/// this doubles as the resize step `set_hires` relies on.
 pub fn clear_display(state: &mut EmulatorState) {
-    state.screen_buffer = [0; SCREEN_WIDTH * SCREEN_HEIGHT];
+    let size = state.width * state.height;
+
+    if state.selected_planes & 0b01 != 0 || state.screen_buffer.len() != size {
+        state.screen_buffer = vec![0; size];
+    }
+    if state.selected_planes & 0b10 != 0 || state.plane2_buffer.len() != size {
+        state.plane2_buffer = vec![0; size];
+    }
+
     state.should_draw = true;
 }
 
+/// Composites `screen_buffer` (plane 0/1) and `plane2_buffer` (plane 2/3)
+/// into a single 2-bit color index per pixel -- bit 0 from `screen_buffer`,
+/// bit 1 from `plane2_buffer` -- so front-ends can rasterize XO-CHIP's two
+/// bit-planes as four colors instead of only ever showing plane 0.
+pub fn composite_planes(state: &EmulatorState) -> Vec<u8> {
+    state
+        .screen_buffer
+        .iter()
+        .zip(&state.plane2_buffer)
+        .map(|(&plane0, &plane2)| (plane0 & 1) | ((plane2 & 1) << 1))
+        .collect()
+}
+
+/// Switches between the lo-res (64x32) and hi-res (128x64) Super-CHIP
+/// display modes, resizing and clearing `screen_buffer`.
+pub fn set_hires(state: &mut EmulatorState, hires: bool) {
+    state.hires = hires;
+    state.width = if hires { HIRES_SCREEN_WIDTH } else { SCREEN_WIDTH };
+    state.height = if hires { HIRES_SCREEN_HEIGHT } else { SCREEN_HEIGHT };
+    clear_display(state);
+}
+
 pub fn load_font(state: &mut EmulatorState) {
     state.memory[..FONT_BYTES.len()].copy_from_slice(&FONT_BYTES);
+    state.memory[LARGE_FONT_OFFSET..LARGE_FONT_OFFSET + LARGE_FONT_BYTES.len()]
+        .copy_from_slice(&LARGE_FONT_BYTES);
 }
 
 pub fn load_rom(state: &mut EmulatorState, path: &Path) -> Result<(), Chip8Error> {
@@ -0,0 +1,247 @@
+use std::io::{stdout, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, Clear, ClearType, EnterAlternateScreen,
+    LeaveAlternateScreen,
+};
+use crossterm::style::{Color, ResetColor, SetBackgroundColor, SetForegroundColor};
+use crossterm::{execute, queue};
+
+use crate::chip8_emulator::config::{KEY_COUNT, MEMORY_SIZE};
+use crate::chip8_emulator::cpu::{execute_cycle, tick_timers};
+use crate::chip8_emulator::debug::{format_post_mortem_trace, DebugController};
+use crate::chip8_emulator::error::Chip8Error;
+use crate::chip8_emulator::quirks::Chip8Quirks;
+use crate::chip8_emulator::save_state::{load_state, quick_save_path, save_state};
+use crate::chip8_emulator::state::{composite_planes, create_state, set_key_state, EmulatorState};
+
+const KEY_MAP: [(KeyCode, usize); 16] = [
+    (KeyCode::Char('1'), 0x1),
+    (KeyCode::Char('2'), 0x2),
+    (KeyCode::Char('3'), 0x3),
+    (KeyCode::Char('4'), 0xC),
+    (KeyCode::Char('q'), 0x4),
+    (KeyCode::Char('w'), 0x5),
+    (KeyCode::Char('e'), 0x6),
+    (KeyCode::Char('r'), 0xD),
+    (KeyCode::Char('a'), 0x7),
+    (KeyCode::Char('s'), 0x8),
+    (KeyCode::Char('d'), 0x9),
+    (KeyCode::Char('f'), 0xE),
+    (KeyCode::Char('z'), 0xA),
+    (KeyCode::Char('x'), 0x0),
+    (KeyCode::Char('c'), 0xB),
+    (KeyCode::Char('v'), 0xF),
+];
+
+/// Guard that restores the terminal (raw mode, alternate screen, cursor) on
+/// drop, including on panic, so a crashing ROM never leaves the user's shell
+/// wedged.
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> std::io::Result<Self> {
+        enable_raw_mode()?;
+        execute!(stdout(), EnterAlternateScreen, Hide, Clear(ClearType::All))?;
+        Ok(Self)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = execute!(stdout(), Show, LeaveAlternateScreen);
+        let _ = disable_raw_mode();
+    }
+}
+
+fn map_key_event(code: KeyCode) -> Option<usize> {
+    KEY_MAP
+        .iter()
+        .find(|(key, _)| *key == code)
+        .map(|(_, mapped)| *mapped)
+}
+
+/// Drains pending terminal input without blocking, applying key-down/key-up
+/// state to `state`, forwarding the pause/step hotkeys to `debugger`,
+/// handling the F5/F9 quick-save/quick-load hotkeys, and returning `true` if
+/// escape was pressed.
+fn poll_input(state: &mut EmulatorState, debugger: &mut DebugController) -> std::io::Result<bool> {
+    let mut escape_pressed = false;
+
+    while event::poll(Duration::ZERO)? {
+        if let Event::Key(key_event) = event::read()? {
+            if key_event.code == KeyCode::Esc {
+                escape_pressed = true;
+                continue;
+            }
+            if key_event.kind != KeyEventKind::Release {
+                if key_event.code == KeyCode::Char(' ') {
+                    debugger.toggle_pause();
+                    continue;
+                }
+                if key_event.code == KeyCode::Char('n') {
+                    debugger.request_step();
+                    continue;
+                }
+                if key_event.code == KeyCode::F(5) {
+                    if let Some(rom_path) = state.rom_path.clone() {
+                        let _ = save_state(state, &quick_save_path(&rom_path));
+                    }
+                    continue;
+                }
+                if key_event.code == KeyCode::F(9) {
+                    if let Some(rom_path) = state.rom_path.clone() {
+                        if let Ok(loaded) = load_state(&quick_save_path(&rom_path)) {
+                            *state = loaded;
+                        }
+                    }
+                    continue;
+                }
+            }
+
+            let Some(mapped) = map_key_event(key_event.code) else {
+                continue;
+            };
+
+            // Terminals without the kitty keyboard protocol never report
+            // `Release`, so a press is treated as a brief tap: held `true`
+            // for one poll and cleared at the top of the next frame.
+            match key_event.kind {
+                KeyEventKind::Release => set_key_state(state, mapped, false),
+                _ => set_key_state(state, mapped, true),
+            }
+        }
+    }
+
+    Ok(escape_pressed)
+}
+
+/// Maps a composited plane index (see `composite_planes`) to a terminal
+/// color: off, plane 0/1 only, plane 2/3 only, or both planes together.
+fn plane_color(value: u8) -> Color {
+    match value {
+        0 => Color::Black,
+        1 => Color::White,
+        2 => Color::Cyan,
+        _ => Color::Yellow,
+    }
+}
+
+/// Renders a composited plane buffer (see `composite_planes`) to the
+/// alternate screen using half-block characters (`▀`), packing two vertical
+/// CHIP-8 pixels into one terminal cell/row: the upper half is drawn in the
+/// top pixel's color, the lower half in the bottom pixel's.
+fn draw_screen(
+    out: &mut impl Write,
+    composited: &[u8],
+    width: usize,
+    height: usize,
+) -> std::io::Result<()> {
+    queue!(out, MoveTo(0, 0))?;
+
+    for cell_row in 0..(height / 2) {
+        queue!(out, MoveTo(0, cell_row as u16))?;
+        for x in 0..width {
+            let top = composited[x + (cell_row * 2) * width];
+            let bottom = composited[x + (cell_row * 2 + 1) * width];
+            queue!(
+                out,
+                SetForegroundColor(plane_color(top)),
+                SetBackgroundColor(plane_color(bottom)),
+            )?;
+            write!(out, "\u{2580}")?;
+        }
+    }
+
+    queue!(out, ResetColor)?;
+    out.flush()
+}
+
+pub fn run_emulator_terminal(
+    quirks: Chip8Quirks,
+    rom_path: &Path,
+    cpu_hz: usize,
+    target_fps: usize,
+    seed: Option<u64>,
+) -> Result<EmulatorState, Chip8Error> {
+    if cpu_hz == 0 {
+        return Err(Chip8Error::InvalidArgument("cpu_hz must be > 0"));
+    }
+    if target_fps == 0 {
+        return Err(Chip8Error::InvalidArgument("target_fps must be > 0"));
+    }
+
+    let mut state = create_state(Some(rom_path), seed)?;
+    let _guard = TerminalGuard::enter()?;
+    let mut out = stdout();
+
+    let cycle_interval = 1.0f32 / cpu_hz as f32;
+    let timer_interval = 1.0f32 / 60.0;
+    let frame_interval = 1.0f32 / target_fps as f32;
+    let max_cycles_per_frame = usize::max(1, (cpu_hz / target_fps) * 3);
+
+    let mut accumulated_time = 0.0f32;
+    let mut timer_accumulated_time = 0.0f32;
+    let mut frame_accumulated_time = 0.0f32;
+    let mut previous_tick = Instant::now();
+    let mut debugger = DebugController::new();
+
+    while !state.exited {
+        // Most terminals never negotiate the Kitty keyboard protocol, so
+        // `Release` events below are unreachable in practice and a key would
+        // otherwise latch on permanently after its first press. Clearing
+        // here every frame is what makes a press register as the documented
+        // brief tap rather than a stuck key.
+        state.key_inputs = [0; KEY_COUNT];
+        if poll_input(&mut state, &mut debugger)? {
+            break;
+        }
+
+        let now = Instant::now();
+        let frame_dt = (now - previous_tick).as_secs_f32().min(0.1);
+        previous_tick = now;
+        accumulated_time += frame_dt;
+        timer_accumulated_time += frame_dt;
+        frame_accumulated_time += frame_dt;
+
+        let mut cycles_run = 0;
+        while accumulated_time >= cycle_interval
+            && cycles_run < max_cycles_per_frame
+            && !state.exited
+        {
+            if !debugger.should_run_cycle(state.pc as u16) {
+                break;
+            }
+
+            if let Err(error) = execute_cycle(&mut state, quirks) {
+                eprintln!("{}", format_post_mortem_trace(&state));
+                return Err(error);
+            }
+            if debugger.paused {
+                debugger.print_dump(&state);
+            }
+            accumulated_time -= cycle_interval;
+            cycles_run += 1;
+        }
+
+        while timer_accumulated_time >= timer_interval && !state.exited {
+            tick_timers(&mut state, None);
+            timer_accumulated_time -= timer_interval;
+        }
+
+        if frame_accumulated_time >= frame_interval {
+            draw_screen(&mut out, &composite_planes(&state), state.width, state.height)?;
+            frame_accumulated_time -= frame_interval;
+        }
+
+        if state.pc >= MEMORY_SIZE {
+            break;
+        }
+    }
+
+    Ok(state)
+}
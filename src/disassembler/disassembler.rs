@@ -0,0 +1,249 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::assembler::error::AssemblerError;
+
+/// Reads `path` and disassembles it as if it were loaded at `origin`.
+pub fn disassemble_file(path: impl AsRef<Path>, origin: usize) -> Result<String, AssemblerError> {
+    let bytes = fs::read(path).map_err(|error| AssemblerError::new(error.to_string(), None))?;
+    disassemble_bytes(&bytes, origin)
+}
+
+/// Disassembles `bytes` into assembly text, treating `bytes[0]` as `origin`.
+/// Jump/call targets get synthetic `Lnnn:` labels; words that don't decode
+/// as a known opcode fall back to `DW 0xNNNN`. Built on top of
+/// `disassemble`, so sprite bytes it identifies come out as `DB` lines here
+/// too.
+pub fn disassemble_bytes(bytes: &[u8], origin: usize) -> Result<String, AssemblerError> {
+    if bytes.len() % 2 != 0 {
+        return Err(AssemblerError::new(
+            format!(
+                "byte slice length {} is not a multiple of 2",
+                bytes.len()
+            ),
+            None,
+        ));
+    }
+
+    let words: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| ((pair[0] as u16) << 8) | pair[1] as u16)
+        .collect();
+    let labels = collect_jump_labels(&words);
+
+    let mut output = String::new();
+    for (address, _opcode, mnemonic) in disassemble(bytes, origin) {
+        if let Some(label) = labels.get(&address) {
+            output.push_str(&format!("{label}:\n"));
+        }
+        output.push_str("    ");
+        output.push_str(&mnemonic);
+        output.push('\n');
+    }
+
+    Ok(output)
+}
+
+/// Decodes `rom` into one `(address, opcode, mnemonic)` triple per 16-bit
+/// word, treating `rom[0]` as loaded at `origin`. A trailing odd byte, if
+/// any, is ignored.
+///
+/// Bytes reached only as sprite data -- read by a `DXYN`/`DXY0` whose most
+/// recent preceding `LD I, addr` points at them -- are
+/// rendered as `DB` lines instead of being mis-decoded as instructions;
+/// everything else is decoded as code.
+pub fn disassemble(rom: &[u8], origin: usize) -> Vec<(usize, u16, String)> {
+    let words: Vec<u16> = rom
+        .chunks_exact(2)
+        .map(|pair| ((pair[0] as u16) << 8) | pair[1] as u16)
+        .collect();
+    let labels = collect_jump_labels(&words);
+    let data_regions = sprite_data_regions(&words);
+
+    words
+        .iter()
+        .enumerate()
+        .map(|(index, &opcode)| {
+            let address = origin + index * 2;
+            let mnemonic = if in_any_region(address, &data_regions) {
+                let [high, low] = opcode.to_be_bytes();
+                format!("DB 0x{high:02X}, 0x{low:02X}")
+            } else {
+                decode_opcode(opcode, &labels)
+            };
+            (address, opcode, mnemonic)
+        })
+        .collect()
+}
+
+fn collect_jump_labels(words: &[u16]) -> HashMap<usize, String> {
+    let mut labels = HashMap::new();
+    for &opcode in words {
+        if let Some(target) = jump_target(opcode) {
+            labels
+                .entry(target)
+                .or_insert_with(|| format!("L{target:03X}"));
+        }
+    }
+    labels
+}
+
+/// Byte ranges `[start, end)` that look like sprite data: the most recent
+/// `LD I, addr` before a `DXYN`/`DXY0` points at `addr`, and `DXYN` reads
+/// `n` bytes from there (`DXY0`'s 16x16 sprites are a fixed 32 bytes, same
+/// as the emulator's own `Dxy0` handler).
+fn sprite_data_regions(words: &[u16]) -> Vec<(usize, usize)> {
+    let mut regions = Vec::new();
+    let mut index_register: Option<usize> = None;
+
+    for &opcode in words {
+        match opcode & 0xF000 {
+            0xA000 => index_register = Some((opcode & 0x0FFF) as usize),
+            0xD000 => {
+                if let Some(start) = index_register {
+                    let n = opcode & 0x000F;
+                    let length = if n == 0 { 32 } else { n as usize };
+                    regions.push((start, start + length));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    regions
+}
+
+fn in_any_region(address: usize, regions: &[(usize, usize)]) -> bool {
+    regions
+        .iter()
+        .any(|&(start, end)| address >= start && address < end)
+}
+
+/// Disassembles `memory[start..end]`, treating `start` as the origin.
+pub fn disassemble_range(
+    memory: &[u8],
+    start: usize,
+    end: usize,
+) -> Result<String, AssemblerError> {
+    if end < start || end > memory.len() {
+        return Err(AssemblerError::new(
+            format!(
+                "invalid range 0x{start:03X}..0x{end:03X} for memory of length {}",
+                memory.len()
+            ),
+            None,
+        ));
+    }
+
+    disassemble_bytes(&memory[start..end], start)
+}
+
+/// Decodes a single opcode into its canonical mnemonic text, with no label
+/// substitution -- jump/call addresses are always printed as `0x{addr:03X}`
+/// rather than a synthetic `Lnnn` label. This is the `encode_instruction`
+/// counterpart: assembling a mnemonic and decoding the resulting opcode with
+/// `disassemble_opcode` recovers an equivalent mnemonic.
+pub fn disassemble_opcode(opcode: u16) -> String {
+    decode_opcode(opcode, &HashMap::new())
+}
+
+/// Decodes `rom` into one mnemonic string per 16-bit word, with no label
+/// substitution (see `disassemble_opcode`). A trailing odd byte, if any, is
+/// ignored.
+pub fn disassemble_rom(rom: &[u8]) -> Vec<String> {
+    rom.chunks_exact(2)
+        .map(|pair| disassemble_opcode(((pair[0] as u16) << 8) | pair[1] as u16))
+        .collect()
+}
+
+fn jump_target(opcode: u16) -> Option<usize> {
+    match opcode & 0xF000 {
+        0x1000 | 0x2000 => Some((opcode & 0x0FFF) as usize),
+        _ => None,
+    }
+}
+
+fn address_operand(address: usize, labels: &HashMap<usize, String>) -> String {
+    labels
+        .get(&address)
+        .cloned()
+        .unwrap_or_else(|| format!("0x{address:03X}"))
+}
+
+fn decode_opcode(opcode: u16, labels: &HashMap<usize, String>) -> String {
+    let x = (opcode & 0x0F00) >> 8;
+    let y = (opcode & 0x00F0) >> 4;
+    let n = opcode & 0x000F;
+    let nn = opcode & 0x00FF;
+    let nnn = (opcode & 0x0FFF) as usize;
+
+    match opcode & 0xF000 {
+        0x0000 => {
+            if opcode & 0xFFF0 == 0x00C0 {
+                format!("SCD 0x{n:X}")
+            } else {
+                match opcode {
+                    0x00E0 => "CLS".to_owned(),
+                    0x00EE => "RET".to_owned(),
+                    0x00FB => "SCR".to_owned(),
+                    0x00FC => "SCL".to_owned(),
+                    0x00FD => "EXIT".to_owned(),
+                    0x00FE => "LOW".to_owned(),
+                    0x00FF => "HIGH".to_owned(),
+                    _ => format!("DW 0x{opcode:04X}"),
+                }
+            }
+        }
+        0x1000 => format!("JP {}", address_operand(nnn, labels)),
+        0x2000 => format!("CALL {}", address_operand(nnn, labels)),
+        0x3000 => format!("SE V{x:X}, 0x{nn:02X}"),
+        0x4000 => format!("SNE V{x:X}, 0x{nn:02X}"),
+        0x5000 => match n {
+            0x0 => format!("SE V{x:X}, V{y:X}"),
+            _ => format!("DW 0x{opcode:04X}"),
+        },
+        0x6000 => format!("LD V{x:X}, 0x{nn:02X}"),
+        0x7000 => format!("ADD V{x:X}, 0x{nn:02X}"),
+        0x8000 => match n {
+            0x0 => format!("LD V{x:X}, V{y:X}"),
+            0x1 => format!("OR V{x:X}, V{y:X}"),
+            0x2 => format!("AND V{x:X}, V{y:X}"),
+            0x3 => format!("XOR V{x:X}, V{y:X}"),
+            0x4 => format!("ADD V{x:X}, V{y:X}"),
+            0x5 => format!("SUB V{x:X}, V{y:X}"),
+            0x6 => format!("SHR V{x:X}, V{y:X}"),
+            0x7 => format!("SUBN V{x:X}, V{y:X}"),
+            0xE => format!("SHL V{x:X}, V{y:X}"),
+            _ => format!("DW 0x{opcode:04X}"),
+        },
+        0x9000 => match n {
+            0x0 => format!("SNE V{x:X}, V{y:X}"),
+            _ => format!("DW 0x{opcode:04X}"),
+        },
+        0xA000 => format!("LD I, 0x{nnn:03X}"),
+        0xB000 => format!("JP V{x:X}, 0x{nn:02X}"),
+        0xC000 => format!("RND V{x:X}, 0x{nn:02X}"),
+        0xD000 => format!("DRW V{x:X}, V{y:X}, 0x{n:X}"),
+        0xE000 => match nn {
+            0x9E => format!("SKP V{x:X}"),
+            0xA1 => format!("SKNP V{x:X}"),
+            _ => format!("DW 0x{opcode:04X}"),
+        },
+        0xF000 => match nn {
+            0x07 => format!("LD V{x:X}, DT"),
+            0x0A => format!("LD V{x:X}, K"),
+            0x15 => format!("LD DT, V{x:X}"),
+            0x18 => format!("LD ST, V{x:X}"),
+            0x1E => format!("ADD I, V{x:X}"),
+            0x29 => format!("LD F, V{x:X}"),
+            0x33 => format!("LD B, V{x:X}"),
+            0x55 => format!("LD [I], V{x:X}"),
+            0x65 => format!("LD V{x:X}, [I]"),
+            0x75 => format!("LD R, V{x:X}"),
+            0x85 => format!("LD V{x:X}, R"),
+            _ => format!("DW 0x{opcode:04X}"),
+        },
+        _ => format!("DW 0x{opcode:04X}"),
+    }
+}
@@ -0,0 +1,6 @@
+pub mod disassembler;
+
+pub use disassembler::{
+    disassemble, disassemble_bytes, disassemble_file, disassemble_opcode, disassemble_range,
+    disassemble_rom,
+};
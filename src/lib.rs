@@ -1,13 +1,22 @@
 pub mod assembler;
 pub mod chip8_emulator;
+pub mod disassembler;
 
 pub use chip8_emulator::app::{run_emulator_app, run_emulator_headless};
-pub use chip8_emulator::cpu::{execute_cycle, execute_opcode, tick_timers};
+pub use chip8_emulator::cpu::{
+    execute_cycle, execute_opcode, run_rom_until, tick_timers, CycleScheduler,
+};
+pub use chip8_emulator::debug::DebugController;
+pub use chip8_emulator::terminal::run_emulator_terminal;
 pub use chip8_emulator::error::Chip8Error;
+pub use chip8_emulator::memory::Memory;
 pub use chip8_emulator::quirks::{
-    load_quirks_profile, load_quirks_profile_from_env, Chip8Quirks, MODERN_QUIRKS, ORIGINAL_QUIRKS,
+    load_quirks_profile, load_quirks_profile_from_env, Chip8Quirks, Platform, MODERN_QUIRKS,
+    ORIGINAL_QUIRKS, SCHIP_QUIRKS, XOCHIP_QUIRKS,
 };
+pub use chip8_emulator::rewind::RewindBuffer;
+pub use chip8_emulator::save_state::{load_state, save_state};
 pub use chip8_emulator::state::{
-    clear_display, create_state, first_pressed_key, load_rom, reset_state, set_key_state,
-    EmulatorState,
+    clear_display, create_state, first_pressed_key, load_rom, reset_state, set_hires,
+    set_key_state, EmulatorState,
 };
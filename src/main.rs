@@ -2,7 +2,10 @@ use std::path::PathBuf;
 
 use clap::Parser;
 
-use chip8_emulator_rs::{load_quirks_profile, run_emulator_app, run_emulator_headless, Chip8Error};
+use chip8_emulator_rs::{
+    load_quirks_profile, run_emulator_app, run_emulator_headless, run_emulator_terminal,
+    Chip8Error,
+};
 
 #[derive(Debug, Parser)]
 #[command(name = "chip8-emulator-rs")]
@@ -11,7 +14,9 @@ struct Args {
     #[arg(long, default_value = "roms/chip8-test-suite.ch8")]
     rom: PathBuf,
 
-    #[arg(long, default_value = "original", value_parser = ["original", "modern"])]
+    #[arg(long, default_value = "original", value_parser = [
+        "original", "modern", "schip", "xochip", "cosmac_vip", "chip48", "superchip",
+    ])]
     quirks: String,
 
     #[arg(long, default_value_t = 16)]
@@ -28,15 +33,26 @@ struct Args {
 
     #[arg(long)]
     headless: bool,
+
+    #[arg(long)]
+    terminal: bool,
+
+    /// Fixes the Cxnn PRNG seed for a reproducible run; omit for entropy.
+    #[arg(long)]
+    seed: Option<u64>,
 }
 
 fn main() -> Result<(), Chip8Error> {
     let args = Args::parse();
-    let quirks = load_quirks_profile(&args.quirks)
-        .map_err(|_| Chip8Error::InvalidArgument("quirks must be original or modern"))?;
+    let quirks = load_quirks_profile(&args.quirks).map_err(|_| {
+        Chip8Error::InvalidArgument(
+            "quirks must be one of: original, modern, schip, xochip, cosmac_vip, chip48, superchip",
+        )
+    })?;
 
     if args.headless {
-        let state = run_emulator_headless(quirks, &args.rom, args.max_cycles, args.hz)?;
+        let state =
+            run_emulator_headless(quirks, &args.rom, args.max_cycles, args.hz, args.seed)?;
         println!(
             "headless finished: exited={} pc=0x{:03x}",
             state.exited, state.pc
@@ -44,6 +60,11 @@ fn main() -> Result<(), Chip8Error> {
         return Ok(());
     }
 
-    let _state = run_emulator_app(quirks, &args.rom, args.scale, args.hz, args.fps)?;
+    if args.terminal {
+        let _state = run_emulator_terminal(quirks, &args.rom, args.hz, args.fps, args.seed)?;
+        return Ok(());
+    }
+
+    let _state = run_emulator_app(quirks, &args.rom, args.scale, args.hz, args.fps, args.seed)?;
     Ok(())
 }
@@ -1,4 +1,8 @@
-use chip8_emulator_rs::assembler::{assemble_text, AssemblerError};
+use chip8_emulator_rs::assembler::{
+    assemble_file, assemble_text, assemble_text_with_debug, assemble_text_with_diagnostics,
+    assemble_text_with_resolver, format_diagnostic, AssemblerError, InMemoryResolver,
+    StatementKind,
+};
 
 #[test]
 fn assemble_basic_program_with_label_jump() {
@@ -73,3 +77,398 @@ fn assemble_errors_on_invalid_register() {
     let result = assemble_text("LD V16, 1", 0x200);
     assert!(matches!(result, Err(AssemblerError { .. })));
 }
+
+#[test]
+fn assemble_expands_a_macro_with_positional_arguments() {
+    let source = "
+        %macro ADD_IMMEDIATE reg, amount
+        ADD \\0, \\1
+        %endmacro
+        ORG 0x200
+        ADD_IMMEDIATE V0, 5
+        ADD_IMMEDIATE V1, 10
+    ";
+
+    let rom = assemble_text(source, 0x200).unwrap();
+
+    assert_eq!(rom, vec![0x70, 0x05, 0x71, 0x0A]);
+}
+
+#[test]
+fn assemble_macro_with_ten_or_more_positional_arguments_does_not_confuse_arg_1_with_arg_10() {
+    let source = "
+        %macro ELEVEN a0, a1, a2, a3, a4, a5, a6, a7, a8, a9, a10
+        DB \\1, \\10
+        %endmacro
+        ORG 0x200
+        ELEVEN 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11
+    ";
+
+    let rom = assemble_text(source, 0x200).unwrap();
+
+    assert_eq!(rom, vec![2, 11]);
+}
+
+#[test]
+fn assemble_expands_a_macro_dot_endm_style_macro_with_a_unique_label_per_call() {
+    let source = "
+        MACRO WAIT_AND_LOOP reg
+        loop:
+        SE \\0, 0
+        JP loop
+        ENDM
+        ORG 0x200
+        WAIT_AND_LOOP V0
+        WAIT_AND_LOOP V1
+    ";
+
+    let rom = assemble_text(source, 0x200).unwrap();
+    let expected = vec![
+        0x30, 0x00, 0x12, 0x00, // loop__WAIT_AND_LOOP1
+        0x31, 0x00, 0x12, 0x04, // loop__WAIT_AND_LOOP2
+    ];
+
+    assert_eq!(rom, expected);
+}
+
+#[test]
+fn assemble_rejects_a_duplicate_macro_definition() {
+    let source = "
+        MACRO FOO a
+        LD \\0, 1
+        ENDM
+        MACRO FOO b
+        LD \\0, 2
+        ENDM
+    ";
+
+    let result = assemble_text(source, 0x200);
+
+    let error = result.unwrap_err();
+    assert!(error.to_string().contains("duplicate macro definition 'FOO'"));
+}
+
+#[test]
+fn assemble_rejects_a_recursive_macro_expansion() {
+    let source = "
+        MACRO FOO a
+        FOO \\0
+        ENDM
+        ORG 0x200
+        FOO V0
+    ";
+
+    let result = assemble_text(source, 0x200);
+
+    let error = result.unwrap_err();
+    assert!(error.to_string().contains("recursive macro expansion"));
+}
+
+#[test]
+fn assemble_rejects_a_macro_call_with_the_wrong_argument_count() {
+    let source = "
+        MACRO FOO a, b
+        LD \\0, \\1
+        ENDM
+        ORG 0x200
+        FOO V0
+    ";
+
+    let result = assemble_text(source, 0x200);
+
+    assert!(matches!(result, Err(AssemblerError { .. })));
+}
+
+#[test]
+fn assemble_file_splices_an_included_file_in_place() {
+    let dir = std::env::temp_dir().join("chip8_emulator_rs_include_test");
+    std::fs::create_dir_all(&dir).unwrap();
+    let included_path = dir.join("sprite.asm");
+    let main_path = dir.join("main.asm");
+
+    std::fs::write(&included_path, "sprite:\n    DB 0xFF\n").unwrap();
+    std::fs::write(
+        &main_path,
+        "ORG 0x200\nLD I, sprite\n#include \"sprite.asm\"\n",
+    )
+    .unwrap();
+
+    let rom = assemble_file(&main_path, 0x200).unwrap();
+
+    std::fs::remove_file(&included_path).ok();
+    std::fs::remove_file(&main_path).ok();
+    std::fs::remove_dir(&dir).ok();
+
+    assert_eq!(rom, vec![0xA2, 0x02, 0xFF]);
+}
+
+#[test]
+fn assemble_file_rejects_an_include_cycle() {
+    let dir = std::env::temp_dir().join("chip8_emulator_rs_include_cycle_test");
+    std::fs::create_dir_all(&dir).unwrap();
+    let a_path = dir.join("a.asm");
+    let b_path = dir.join("b.asm");
+
+    std::fs::write(&a_path, "#include \"b.asm\"\n").unwrap();
+    std::fs::write(&b_path, "#include \"a.asm\"\n").unwrap();
+
+    let result = assemble_file(&a_path, 0x200);
+
+    std::fs::remove_file(&a_path).ok();
+    std::fs::remove_file(&b_path).ok();
+    std::fs::remove_dir(&dir).ok();
+
+    assert!(matches!(result, Err(AssemblerError { .. })));
+}
+
+#[test]
+fn assemble_file_splices_an_included_file_via_the_include_directive() {
+    let dir = std::env::temp_dir().join("chip8_emulator_rs_include_keyword_test");
+    std::fs::create_dir_all(&dir).unwrap();
+    let included_path = dir.join("sprite.asm");
+    let main_path = dir.join("main.asm");
+
+    std::fs::write(&included_path, "sprite:\n    DB 0xFF\n").unwrap();
+    std::fs::write(
+        &main_path,
+        "ORG 0x200\nLD I, sprite\nINCLUDE \"sprite.asm\"\n",
+    )
+    .unwrap();
+
+    let rom = assemble_file(&main_path, 0x200).unwrap();
+
+    std::fs::remove_file(&included_path).ok();
+    std::fs::remove_file(&main_path).ok();
+    std::fs::remove_dir(&dir).ok();
+
+    assert_eq!(rom, vec![0xA2, 0x02, 0xFF]);
+}
+
+#[test]
+fn assemble_text_with_resolver_splices_an_in_memory_library_via_include() {
+    let resolver = InMemoryResolver::new().with_file("sprite_lib", "sprite:\n    DB 0xFF\n");
+
+    let source = "ORG 0x200\nLD I, sprite\nINCLUDE \"sprite_lib\"\n";
+
+    let rom = assemble_text_with_resolver(source, 0x200, Box::new(resolver)).unwrap();
+
+    assert_eq!(rom, vec![0xA2, 0x02, 0xFF]);
+}
+
+#[test]
+fn assemble_text_with_resolver_rejects_an_include_cycle() {
+    let resolver = InMemoryResolver::new()
+        .with_file("a", "INCLUDE \"b\"\n")
+        .with_file("b", "INCLUDE \"a\"\n");
+
+    let source = "INCLUDE \"a\"\n";
+
+    let result = assemble_text_with_resolver(source, 0x200, Box::new(resolver));
+
+    assert!(matches!(result, Err(AssemblerError { .. })));
+}
+
+#[test]
+fn assemble_schip_extended_mode_opcodes() {
+    let source = "
+        HIGH
+        LOW
+        SCR
+        SCL
+        SCD 0x5
+        LD R, V3
+        LD V3, R
+        DRW V1, V2, 0
+    ";
+
+    let rom = assemble_text(source, 0x200).unwrap();
+    let expected = vec![
+        0x00, 0xFF, 0x00, 0xFE, 0x00, 0xFB, 0x00, 0xFC, 0x00, 0xC5, 0xF3, 0x75, 0xF3, 0x85, 0xD1,
+        0x20,
+    ];
+
+    assert_eq!(rom, expected);
+}
+
+#[test]
+fn assemble_resolves_an_equ_constant_used_as_an_instruction_operand() {
+    let source = "
+        WIDTH EQU 64
+        ORG 0x200
+        LD V0, WIDTH
+    ";
+
+    let rom = assemble_text(source, 0x200).unwrap();
+
+    assert_eq!(rom, vec![0x60, 0x40]);
+}
+
+#[test]
+fn assemble_resolves_an_equ_constant_that_forward_references_labels() {
+    let source = "
+        ORG 0x200
+        LD I, SPRITE_LEN
+    sprite_start:
+        DB 1, 2, 3
+    sprite_end:
+        SPRITE_LEN EQU sprite_end - sprite_start
+    ";
+
+    let rom = assemble_text(source, 0x200).unwrap();
+
+    assert_eq!(rom, vec![0xA0, 0x03, 0x01, 0x02, 0x03]);
+}
+
+#[test]
+fn assemble_resolves_an_equ_constant_that_forward_references_another_equ_constant() {
+    let source = "
+        A EQU B + 1
+        B EQU 2
+        ORG 0x200
+        LD V0, A
+    ";
+
+    let rom = assemble_text(source, 0x200).unwrap();
+
+    assert_eq!(rom, vec![0x60, 0x03]);
+}
+
+#[test]
+fn assemble_evaluates_arithmetic_expressions_with_parentheses_in_operands() {
+    let source = "
+        ORG 0x200
+        LD V0, (2 + 3) * 4
+        DB 2 * 3 + 1
+        DW 2 + 3
+    ";
+
+    let rom = assemble_text(source, 0x200).unwrap();
+
+    assert_eq!(rom, vec![0x60, 0x14, 0x07, 0x00, 0x05]);
+}
+
+#[test]
+fn assemble_rejects_a_duplicate_equ_constant() {
+    let source = "
+        WIDTH EQU 64
+        WIDTH EQU 32
+    ";
+
+    let result = assemble_text(source, 0x200);
+
+    let error = result.unwrap_err();
+    assert!(error.to_string().contains("duplicate EQU constant 'WIDTH'"));
+}
+
+#[test]
+fn assemble_rejects_a_label_that_redefines_an_equ_constant() {
+    let source = "
+        WIDTH EQU 64
+    WIDTH:
+        RET
+    ";
+
+    let result = assemble_text(source, 0x200);
+
+    let error = result.unwrap_err();
+    assert!(error.to_string().contains("duplicate symbol 'WIDTH'"));
+}
+
+#[test]
+fn assemble_rejects_division_by_zero_in_an_expression() {
+    let source = "
+        ORG 0x200
+        LD V0, 5 / 0
+    ";
+
+    let result = assemble_text(source, 0x200);
+
+    let error = result.unwrap_err();
+    assert!(error.to_string().contains("division by zero"));
+}
+
+#[test]
+fn assemble_with_diagnostics_collects_every_independent_error_in_one_pass() {
+    let source = "
+        ORG 0x200
+        LD V16, 1
+        ADD V0, 999
+        JP undefined_label
+    ";
+
+    let errors = assemble_text_with_diagnostics(source, 0x200).unwrap_err();
+
+    assert_eq!(errors.len(), 3, "errors: {errors:?}");
+}
+
+#[test]
+fn assemble_with_diagnostics_keeps_the_two_byte_size_estimate_for_a_bad_instruction() {
+    let source = "
+        ORG 0x200
+        LD V16, 1
+        JP 0x206
+    ";
+
+    let errors = assemble_text_with_diagnostics(source, 0x200).unwrap_err();
+
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].to_string().contains("unsupported LD form: V16, 1"));
+}
+
+#[test]
+fn format_diagnostic_underlines_the_argument_list() {
+    let result = assemble_text("ADD V0, 999", 0x200);
+
+    let error = result.unwrap_err();
+    let report = format_diagnostic(&error);
+
+    let lines: Vec<&str> = report.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[1].contains("ADD V0, 999"));
+    assert_eq!(lines[2].trim(), "^^^^^^^");
+}
+
+#[test]
+fn assemble_with_debug_maps_each_byte_address_back_to_its_source_line() {
+    let source = "
+        ORG 0x200
+        LD V0, 1
+        DB 1, 2, 3
+        JP 0x200
+    ";
+
+    let (rom, debug_map) = assemble_text_with_debug(source, 0x200).unwrap();
+
+    assert_eq!(rom.len(), 7);
+    assert_eq!(debug_map.entries().len(), 4);
+
+    let ld_entry = debug_map.lookup(0x200).unwrap();
+    assert_eq!(ld_entry.address_start, 0x200);
+    assert_eq!(ld_entry.address_end, 0x202);
+    assert_eq!(ld_entry.position.line, 3);
+    assert_eq!(ld_entry.statement_kind, StatementKind::Instruction);
+
+    let db_entry = debug_map.lookup(0x203).unwrap();
+    assert_eq!(db_entry.address_start, 0x202);
+    assert_eq!(db_entry.address_end, 0x205);
+    assert_eq!(db_entry.statement_kind, StatementKind::DirectiveDb);
+
+    assert!(debug_map.lookup(0x300).is_none());
+}
+
+#[test]
+fn assemble_reports_both_positions_of_a_duplicate_label() {
+    let source = "
+        ORG 0x200
+    start:
+        CLS
+    start:
+        RET
+    ";
+
+    let result = assemble_text(source, 0x200);
+
+    let error = result.unwrap_err();
+    assert!(error.to_string().contains("duplicate label 'start'"));
+    assert!(error.to_string().contains("first defined"));
+}
@@ -1,10 +1,11 @@
 use chip8_emulator_rs::{
-    create_state, execute_cycle, execute_opcode, tick_timers, MODERN_QUIRKS, ORIGINAL_QUIRKS,
+    create_state, execute_cycle, execute_opcode, tick_timers, Chip8Error, CycleScheduler,
+    MODERN_QUIRKS, ORIGINAL_QUIRKS, XOCHIP_QUIRKS,
 };
 
 #[test]
 fn ex9e_skips_when_key_pressed() {
-    let mut state = create_state(None).unwrap();
+    let mut state = create_state(None, None).unwrap();
     state.registers[1] = 0xA;
     state.key_inputs[0xA] = 1;
     let start_pc = state.pc;
@@ -16,7 +17,7 @@ fn ex9e_skips_when_key_pressed() {
 
 #[test]
 fn exa1_skips_when_key_not_pressed() {
-    let mut state = create_state(None).unwrap();
+    let mut state = create_state(None, None).unwrap();
     state.registers[1] = 0xA;
     state.key_inputs[0xA] = 0;
     let start_pc = state.pc;
@@ -28,7 +29,7 @@ fn exa1_skips_when_key_not_pressed() {
 
 #[test]
 fn fx33_stores_bcd_digits() {
-    let mut state = create_state(None).unwrap();
+    let mut state = create_state(None, None).unwrap();
     state.registers[2] = 231;
     state.index = 0x300;
 
@@ -39,7 +40,7 @@ fn fx33_stores_bcd_digits() {
 
 #[test]
 fn fx65_reads_registers_and_increments_i() {
-    let mut state = create_state(None).unwrap();
+    let mut state = create_state(None, None).unwrap();
     state.index = 0x300;
     state.memory[0x300..0x303].copy_from_slice(&[0xAA, 0xBB, 0xCC]);
 
@@ -51,7 +52,7 @@ fn fx65_reads_registers_and_increments_i() {
 
 #[test]
 fn seven_xnn_wraps_at_8_bits() {
-    let mut state = create_state(None).unwrap();
+    let mut state = create_state(None, None).unwrap();
     state.registers[0] = 0xFF;
 
     execute_opcode(&mut state, 0x7002, ORIGINAL_QUIRKS).unwrap();
@@ -61,7 +62,7 @@ fn seven_xnn_wraps_at_8_bits() {
 
 #[test]
 fn eight_xy6_uses_vy_as_source() {
-    let mut state = create_state(None).unwrap();
+    let mut state = create_state(None, None).unwrap();
     state.registers[1] = 0x00;
     state.registers[2] = 0x03;
 
@@ -74,7 +75,7 @@ fn eight_xy6_uses_vy_as_source() {
 
 #[test]
 fn eight_xye_uses_vy_as_source() {
-    let mut state = create_state(None).unwrap();
+    let mut state = create_state(None, None).unwrap();
     state.registers[1] = 0x00;
     state.registers[2] = 0x80;
 
@@ -87,7 +88,7 @@ fn eight_xye_uses_vy_as_source() {
 
 #[test]
 fn dxyn_sets_collision_flag_without_losing_it() {
-    let mut state = create_state(None).unwrap();
+    let mut state = create_state(None, None).unwrap();
     state.registers[0] = 2;
     state.registers[1] = 3;
     state.index = 0x300;
@@ -103,7 +104,7 @@ fn dxyn_sets_collision_flag_without_losing_it() {
 
 #[test]
 fn dxyn_wraps_start_coordinates() {
-    let mut state = create_state(None).unwrap();
+    let mut state = create_state(None, None).unwrap();
     state.registers[0] = 66;
     state.registers[1] = 33;
     state.index = 0x300;
@@ -117,7 +118,7 @@ fn dxyn_wraps_start_coordinates() {
 
 #[test]
 fn eight_xy6_uses_vx_source_in_modern_profile() {
-    let mut state = create_state(None).unwrap();
+    let mut state = create_state(None, None).unwrap();
     state.registers[1] = 0x03;
     state.registers[2] = 0x00;
 
@@ -129,7 +130,7 @@ fn eight_xy6_uses_vx_source_in_modern_profile() {
 
 #[test]
 fn fx65_does_not_increment_i_in_modern_profile() {
-    let mut state = create_state(None).unwrap();
+    let mut state = create_state(None, None).unwrap();
     state.index = 0x300;
     state.memory[0x300..0x303].copy_from_slice(&[0xAA, 0xBB, 0xCC]);
 
@@ -141,7 +142,7 @@ fn fx65_does_not_increment_i_in_modern_profile() {
 
 #[test]
 fn fx55_does_not_increment_i_in_modern_profile() {
-    let mut state = create_state(None).unwrap();
+    let mut state = create_state(None, None).unwrap();
     state.index = 0x300;
     state.registers[0..3].copy_from_slice(&[0x11, 0x22, 0x33]);
 
@@ -153,7 +154,7 @@ fn fx55_does_not_increment_i_in_modern_profile() {
 
 #[test]
 fn bxnn_jump_uses_vx_in_modern_profile() {
-    let mut state = create_state(None).unwrap();
+    let mut state = create_state(None, None).unwrap();
     state.registers[0] = 0x05;
     state.registers[1] = 0x10;
 
@@ -164,7 +165,7 @@ fn bxnn_jump_uses_vx_in_modern_profile() {
 
 #[test]
 fn dxyn_wraps_pixels_in_modern_profile() {
-    let mut state = create_state(None).unwrap();
+    let mut state = create_state(None, None).unwrap();
     state.registers[0] = 63;
     state.registers[1] = 31;
     state.index = 0x300;
@@ -180,7 +181,7 @@ fn dxyn_wraps_pixels_in_modern_profile() {
 
 #[test]
 fn execute_cycle_does_not_tick_timers() {
-    let mut state = create_state(None).unwrap();
+    let mut state = create_state(None, None).unwrap();
     state.delay_timer = 5;
     state.sound_timer = 5;
     state.memory[state.pc] = 0x00;
@@ -194,7 +195,7 @@ fn execute_cycle_does_not_tick_timers() {
 
 #[test]
 fn tick_timers_decrements_sound_and_delay() {
-    let mut state = create_state(None).unwrap();
+    let mut state = create_state(None, None).unwrap();
     state.delay_timer = 2;
     state.sound_timer = 2;
     let mut beep_count = 0;
@@ -216,3 +217,159 @@ fn tick_timers_decrements_sound_and_delay() {
     assert_eq!(state.sound_timer, 0);
     assert_eq!(beep_count, 2);
 }
+
+#[test]
+fn cycle_scheduler_runs_configured_cycles_and_ticks_timers_once() {
+    let mut state = create_state(None, None).unwrap();
+    state.delay_timer = 10;
+    // Four CLS instructions; enough that `cycles_per_frame` stops short of
+    // running them all.
+    for i in 0..4 {
+        state.memory[state.pc + i * 2] = 0x00;
+        state.memory[state.pc + i * 2 + 1] = 0xE0;
+    }
+    let start_pc = state.pc;
+    let mut scheduler = CycleScheduler::new(3);
+    assert_eq!(scheduler.cycles_per_frame(), 3);
+
+    scheduler.run_frame(&mut state, ORIGINAL_QUIRKS, None).unwrap();
+
+    assert_eq!(state.pc, start_pc + 6, "should run exactly 3 cycles");
+    assert_eq!(state.delay_timer, 9, "should apply exactly one timer tick");
+
+    scheduler.set_cycles_per_frame(1);
+    scheduler.run_frame(&mut state, ORIGINAL_QUIRKS, None).unwrap();
+
+    assert_eq!(state.pc, start_pc + 8);
+    assert_eq!(state.delay_timer, 8);
+}
+
+#[test]
+fn display_wait_quirk_blocks_execution_until_next_vblank() {
+    let mut state = create_state(None, None).unwrap();
+    assert!(ORIGINAL_QUIRKS.display_wait);
+    let start_pc = state.pc;
+
+    execute_opcode(&mut state, 0xD001, ORIGINAL_QUIRKS).unwrap();
+    assert!(state.waiting_for_vblank);
+
+    execute_cycle(&mut state, ORIGINAL_QUIRKS).unwrap();
+    assert_eq!(state.pc, start_pc, "cycle should be a no-op while waiting for vblank");
+
+    tick_timers(&mut state, None);
+    assert!(!state.waiting_for_vblank);
+}
+
+#[test]
+fn display_wait_quirk_is_off_under_modern_profile() {
+    assert!(!MODERN_QUIRKS.display_wait);
+
+    let mut state = create_state(None, None).unwrap();
+    execute_opcode(&mut state, 0xD001, MODERN_QUIRKS).unwrap();
+
+    assert!(!state.waiting_for_vblank);
+}
+
+#[test]
+fn fn01_selects_the_active_xochip_bit_planes() {
+    let mut state = create_state(None, None).unwrap();
+    assert_eq!(state.selected_planes, 0b01);
+
+    execute_opcode(&mut state, 0xF201, XOCHIP_QUIRKS).unwrap();
+    assert_eq!(state.selected_planes, 0b10);
+
+    execute_opcode(&mut state, 0xF301, XOCHIP_QUIRKS).unwrap();
+    assert_eq!(state.selected_planes, 0b11);
+
+    execute_opcode(&mut state, 0xF001, XOCHIP_QUIRKS).unwrap();
+    assert_eq!(state.selected_planes, 0b00);
+}
+
+#[test]
+fn dxyn_draws_into_the_second_plane_only_when_selected() {
+    let mut state = create_state(None, None).unwrap();
+    state.index = 0x300;
+    state.memory[0x300] = 0b1000_0000;
+
+    execute_opcode(&mut state, 0xF201, XOCHIP_QUIRKS).unwrap();
+    execute_opcode(&mut state, 0xD001, XOCHIP_QUIRKS).unwrap();
+
+    assert_eq!(state.screen_buffer[0], 0, "plane 0 was not selected");
+    assert_eq!(state.plane2_buffer[0], 1);
+}
+
+#[test]
+fn dxyn_draws_into_both_planes_from_sequential_sprite_data() {
+    let mut state = create_state(None, None).unwrap();
+    state.index = 0x300;
+    state.memory[0x300] = 0b1000_0000; // plane 0 row
+    state.memory[0x301] = 0b1000_0000; // plane 1 row
+
+    execute_opcode(&mut state, 0xF301, XOCHIP_QUIRKS).unwrap();
+    execute_opcode(&mut state, 0xD001, XOCHIP_QUIRKS).unwrap();
+
+    assert_eq!(state.screen_buffer[0], 1);
+    assert_eq!(state.plane2_buffer[0], 1);
+}
+
+#[test]
+fn fx75_and_fx85_round_trip_all_sixteen_rpl_flags_on_xochip() {
+    let mut state = create_state(None, None).unwrap();
+    for (index, register) in state.registers.iter_mut().enumerate() {
+        *register = index as u8 + 1;
+    }
+
+    execute_opcode(&mut state, 0xFF75, XOCHIP_QUIRKS).unwrap();
+    state.registers = [0; 16];
+    execute_opcode(&mut state, 0xFF85, XOCHIP_QUIRKS).unwrap();
+
+    for (index, register) in state.registers.iter().enumerate() {
+        assert_eq!(*register, index as u8 + 1);
+    }
+}
+
+#[test]
+fn fx33_reports_memory_out_of_bounds_instead_of_panicking() {
+    let mut state = create_state(None, None).unwrap();
+    state.index = 4094; // leaves room for only 2 of the 3 BCD digit writes
+    state.registers[0] = 231;
+
+    let result = execute_opcode(&mut state, 0xF033, ORIGINAL_QUIRKS);
+
+    assert!(matches!(result, Err(Chip8Error::MemoryOutOfBounds(4096))));
+}
+
+#[test]
+fn fx65_reports_memory_out_of_bounds_instead_of_panicking() {
+    let mut state = create_state(None, None).unwrap();
+    state.index = 4095;
+
+    let result = execute_opcode(&mut state, 0xF165, ORIGINAL_QUIRKS);
+
+    assert!(matches!(result, Err(Chip8Error::MemoryOutOfBounds(4096))));
+}
+
+#[test]
+fn call_reports_stack_overflow_past_sixteen_nested_calls() {
+    let mut state = create_state(None, None).unwrap();
+    for _ in 0..16 {
+        state.stack.push(0x200);
+    }
+
+    let result = execute_opcode(&mut state, 0x2300, ORIGINAL_QUIRKS);
+
+    assert!(matches!(result, Err(Chip8Error::StackOverflow)));
+    assert_eq!(state.stack.len(), 16, "the failed call must not grow the stack");
+}
+
+#[test]
+fn dxyn_reports_memory_out_of_bounds_for_sprite_data_past_the_end_of_memory() {
+    let mut state = create_state(None, None).unwrap();
+    state.index = 4095;
+    state.registers[0] = 0;
+    state.registers[1] = 0;
+
+    let result = execute_opcode(&mut state, 0xD012, ORIGINAL_QUIRKS);
+
+    assert!(matches!(result, Err(Chip8Error::MemoryOutOfBounds(4096))));
+}
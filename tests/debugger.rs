@@ -0,0 +1,102 @@
+use chip8_emulator_rs::{create_state, DebugController, MODERN_QUIRKS};
+
+#[test]
+fn step_runs_exactly_one_cycle_and_pauses_on_breakpoint() {
+    let mut state = create_state(None, None).unwrap();
+    let start_pc = state.pc as u16;
+    let mut debugger = DebugController::new();
+    debugger.add_breakpoint(start_pc + 2);
+
+    debugger.step(&mut state, MODERN_QUIRKS).unwrap();
+
+    assert_eq!(state.pc as u16, start_pc + 2);
+    assert!(debugger.paused);
+    assert_eq!(debugger.step_counter, 1);
+}
+
+#[test]
+fn continue_until_break_stops_at_a_breakpoint() {
+    let mut state = create_state(None, None).unwrap();
+    let start_pc = state.pc as u16;
+    let mut debugger = DebugController::new();
+    debugger.add_breakpoint(start_pc + 6);
+
+    debugger.continue_until_break(&mut state, MODERN_QUIRKS).unwrap();
+
+    assert_eq!(state.pc as u16, start_pc + 6);
+    assert!(debugger.paused);
+}
+
+#[test]
+fn watchpoint_pauses_when_the_watched_byte_changes() {
+    let mut state = create_state(None, None).unwrap();
+    state.index = 0x300;
+    state.registers[0] = 0xAB;
+    // `LD [I], V0` at the program start writes registers[0..=0] to memory.
+    state.memory[state.pc] = 0xF0;
+    state.memory[state.pc + 1] = 0x55;
+
+    let mut debugger = DebugController::new();
+    debugger.add_watchpoint(0x300);
+
+    debugger.step(&mut state, MODERN_QUIRKS).unwrap();
+
+    assert!(debugger.paused);
+    assert_eq!(state.memory[0x300], 0xAB);
+}
+
+#[test]
+fn execute_command_dumps_registers_and_sets_breakpoints() {
+    let mut state = create_state(None, None).unwrap();
+    state.registers[2] = 0x42;
+    let mut debugger = DebugController::new();
+
+    let regs = debugger.execute_command("regs", &mut state, MODERN_QUIRKS).unwrap();
+    assert!(regs.contains("v2=0x42"));
+
+    debugger.execute_command("break 0x202", &mut state, MODERN_QUIRKS).unwrap();
+    assert!(debugger.breakpoints.contains(&0x202));
+
+    debugger.execute_command("clear 0x202", &mut state, MODERN_QUIRKS).unwrap();
+    assert!(!debugger.breakpoints.contains(&0x202));
+}
+
+#[test]
+fn execute_command_step_with_a_count_repeats_the_step() {
+    let mut state = create_state(None, None).unwrap();
+    let start_pc = state.pc as u16;
+    let mut debugger = DebugController::new();
+
+    debugger
+        .execute_command("step 3", &mut state, MODERN_QUIRKS)
+        .unwrap();
+
+    assert_eq!(state.pc as u16, start_pc + 6);
+    assert_eq!(debugger.step_counter, 3);
+}
+
+#[test]
+fn execute_command_step_with_a_count_stops_early_at_a_breakpoint() {
+    let mut state = create_state(None, None).unwrap();
+    let start_pc = state.pc as u16;
+    let mut debugger = DebugController::new();
+    debugger.add_breakpoint(start_pc + 2);
+
+    debugger
+        .execute_command("step 5", &mut state, MODERN_QUIRKS)
+        .unwrap();
+
+    assert_eq!(state.pc as u16, start_pc + 2);
+    assert_eq!(debugger.step_counter, 1);
+    assert!(debugger.paused);
+}
+
+#[test]
+fn execute_command_rejects_unknown_commands() {
+    let mut state = create_state(None, None).unwrap();
+    let mut debugger = DebugController::new();
+
+    assert!(debugger
+        .execute_command("frobnicate", &mut state, MODERN_QUIRKS)
+        .is_err());
+}
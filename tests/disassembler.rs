@@ -0,0 +1,142 @@
+use chip8_emulator_rs::assembler::assemble_text;
+use chip8_emulator_rs::disassembler::{
+    disassemble, disassemble_bytes, disassemble_opcode, disassemble_range, disassemble_rom,
+};
+
+#[test]
+fn disassemble_basic_program_with_label_jump() {
+    let rom = vec![0x60, 0x01, 0x70, 0x02, 0x12, 0x00];
+
+    let text = disassemble_bytes(&rom, 0x200).unwrap();
+
+    assert_eq!(
+        text,
+        "L200:\n    LD V0, 0x01\n    ADD V0, 0x02\n    JP L200\n"
+    );
+}
+
+#[test]
+fn disassemble_round_trips_through_the_assembler() {
+    let source = "
+        ORG 0x200
+    start:
+        LD V0, 1
+        ADD V0, 2
+        JP start
+    ";
+    let rom = assemble_text(source, 0x200).unwrap();
+
+    let text = disassemble_bytes(&rom, 0x200).unwrap();
+    let reassembled = assemble_text(&text, 0x200).unwrap();
+
+    assert_eq!(reassembled, rom);
+}
+
+#[test]
+fn disassemble_ld_variants_and_draw() {
+    let rom = vec![
+        0xA2, 0x12, 0xF1, 0x07, 0xF1, 0x15, 0xF1, 0x18, 0xF1, 0x29, 0xF1, 0x33, 0xF1, 0x55, 0xF1,
+        0x65, 0xD1, 0x25,
+    ];
+
+    let text = disassemble_bytes(&rom, 0x200).unwrap();
+
+    assert_eq!(
+        text,
+        "    LD I, 0x212\n    LD V1, DT\n    LD DT, V1\n    LD ST, V1\n    LD F, V1\n    LD B, V1\n    LD [I], V1\n    LD V1, [I]\n    DRW V1, V2, 0x5\n"
+    );
+}
+
+#[test]
+fn disassemble_falls_back_to_dw_for_unknown_opcode() {
+    let rom = vec![0x50, 0x01]; // 0x5001: low nibble of 5XY0 family must be 0
+
+    let text = disassemble_bytes(&rom, 0x200).unwrap();
+
+    assert_eq!(text, "    DW 0x5001\n");
+}
+
+#[test]
+fn disassemble_range_rejects_out_of_bounds_slice() {
+    let memory = [0u8; 4];
+
+    let result = disassemble_range(&memory, 2, 8);
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn disassemble_bytes_rejects_odd_length() {
+    let result = disassemble_bytes(&[0x60], 0x200);
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn disassemble_returns_one_address_opcode_mnemonic_triple_per_word() {
+    let rom = vec![0x60, 0x01, 0x70, 0x02];
+
+    let entries = disassemble(&rom, 0x200);
+
+    assert_eq!(
+        entries,
+        vec![
+            (0x200, 0x6001, "LD V0, 0x01".to_owned()),
+            (0x202, 0x7002, "ADD V0, 0x02".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn disassemble_annotates_sprite_bytes_reached_via_ld_i_and_drw_as_data() {
+    // LD I, 0x204 ; DRW V0, V1, 2 ; then 2 bytes of sprite data at 0x204
+    let rom = vec![0xA2, 0x04, 0xD0, 0x12, 0xFF, 0x81];
+
+    let entries = disassemble(&rom, 0x200);
+
+    assert_eq!(entries[2], (0x204, 0xFF81, "DB 0xFF, 0x81".to_owned()));
+
+    let text = disassemble_bytes(&rom, 0x200).unwrap();
+    assert_eq!(text, "    LD I, 0x204\n    DRW V0, V1, 0x2\n    DB 0xFF, 0x81\n");
+}
+
+#[test]
+fn disassemble_opcode_decodes_without_any_label_substitution() {
+    assert_eq!(disassemble_opcode(0x6005), "LD V0, 0x05");
+    assert_eq!(disassemble_opcode(0xD123), "DRW V1, V2, 0x3");
+    assert_eq!(disassemble_opcode(0x1200), "JP 0x200");
+    assert_eq!(disassemble_opcode(0x00E0), "CLS");
+}
+
+#[test]
+fn disassemble_opcode_decodes_schip_extended_mode_opcodes() {
+    assert_eq!(disassemble_opcode(0x00FF), "HIGH");
+    assert_eq!(disassemble_opcode(0x00FE), "LOW");
+    assert_eq!(disassemble_opcode(0x00FB), "SCR");
+    assert_eq!(disassemble_opcode(0x00FC), "SCL");
+    assert_eq!(disassemble_opcode(0x00C5), "SCD 0x5");
+    assert_eq!(disassemble_opcode(0xD120), "DRW V1, V2, 0x0");
+    assert_eq!(disassemble_opcode(0xF375), "LD R, V3");
+    assert_eq!(disassemble_opcode(0xF385), "LD V3, R");
+}
+
+#[test]
+fn disassemble_rom_decodes_assembled_program_opcode_by_opcode() {
+    let source = "
+        LD V0, 1
+        ADD V0, 2
+        DRW V1, V2, 5
+    ";
+    let rom = assemble_text(source, 0x200).unwrap();
+
+    let mnemonics = disassemble_rom(&rom);
+
+    assert_eq!(
+        mnemonics,
+        vec![
+            "LD V0, 0x01".to_owned(),
+            "ADD V0, 0x02".to_owned(),
+            "DRW V1, V2, 0x5".to_owned(),
+        ]
+    );
+}
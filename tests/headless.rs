@@ -5,7 +5,7 @@ fn headless_stops_on_exit_opcode() {
     let tmp = tempfile::NamedTempFile::new().unwrap();
     std::fs::write(tmp.path(), [0x00, 0xFD]).unwrap();
 
-    let state = run_emulator_headless(ORIGINAL_QUIRKS, tmp.path(), 10, 700).unwrap();
+    let state = run_emulator_headless(ORIGINAL_QUIRKS, tmp.path(), 10, 700, None).unwrap();
 
     assert!(state.exited);
 }
@@ -14,7 +14,7 @@ fn headless_stops_on_exit_opcode() {
 fn white_dot_rom_draws_pixels() {
     let rom_path =
         std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("roms/white_dot_wasd.ch8");
-    let state = run_emulator_headless(ORIGINAL_QUIRKS, &rom_path, 64, 700).unwrap();
+    let state = run_emulator_headless(ORIGINAL_QUIRKS, &rom_path, 64, 700, None).unwrap();
 
     assert!(
         state.screen_buffer.iter().any(|pixel| *pixel == 1),
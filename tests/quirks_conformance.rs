@@ -0,0 +1,184 @@
+use chip8_emulator_rs::assembler::assemble_text;
+use chip8_emulator_rs::{
+    create_state, run_rom_until, Chip8Quirks, EmulatorState, MODERN_QUIRKS, ORIGINAL_QUIRKS,
+};
+
+/// Assembles `source`, loads it at 0x200 into a fresh state, and runs it for
+/// `cycles` cycles under `quirks`. Shared by every test below so a
+/// regression in `run_rom_until`/`execute_cycle` itself is caught alongside
+/// regressions in the opcodes they exercise.
+fn run_program(source: &str, quirks: Chip8Quirks, cycles: usize) -> EmulatorState {
+    let rom = assemble_text(source, 0x200).unwrap();
+    let mut state = create_state(None, Some(0)).unwrap();
+    state.memory[0x200..0x200 + rom.len()].copy_from_slice(&rom);
+
+    run_rom_until(&mut state, quirks, cycles).unwrap();
+    state
+}
+
+#[test]
+fn shr_honors_shift_uses_vy_quirk() {
+    let source = "
+        LD V0, 0x05
+        LD V1, 0x03
+        SHR V0, V1
+    ";
+
+    for shift_uses_vy in [false, true] {
+        let quirks = Chip8Quirks {
+            shift_uses_vy,
+            ..MODERN_QUIRKS
+        };
+        let state = run_program(source, quirks, 3);
+
+        let expected = if shift_uses_vy { 0x03 >> 1 } else { 0x05 >> 1 };
+        assert_eq!(state.registers[0], expected, "shift_uses_vy={shift_uses_vy}");
+        assert_eq!(state.registers[0xF], 1, "both sources are odd, so VF should be 1");
+    }
+}
+
+#[test]
+fn bnnn_honors_jump_with_vx_quirk() {
+    let source = "
+        LD V0, 0x10
+        LD V2, 0x01
+        JP V2, 0x45
+    ";
+
+    for jump_with_vx in [false, true] {
+        let quirks = Chip8Quirks {
+            jump_with_vx,
+            ..MODERN_QUIRKS
+        };
+        let state = run_program(source, quirks, 3);
+
+        let expected = if jump_with_vx { 0x245 + 0x01 } else { 0x245 + 0x10 };
+        assert_eq!(state.pc, expected, "jump_with_vx={jump_with_vx}");
+    }
+}
+
+#[test]
+fn fx55_fx65_honor_load_store_increment_i_quirk() {
+    let source = "
+        LD V0, 0xAA
+        LD V1, 0xBB
+        LD I, 0x300
+        LD [I], V1
+        LD V0, 0x00
+        LD V1, 0x00
+        LD I, 0x300
+        LD V1, [I]
+    ";
+
+    for load_store_increment_i in [false, true] {
+        let quirks = Chip8Quirks {
+            load_store_increment_i,
+            ..MODERN_QUIRKS
+        };
+        let state = run_program(source, quirks, 8);
+
+        assert_eq!(state.registers[0], 0xAA);
+        assert_eq!(state.registers[1], 0xBB);
+        let expected_index = if load_store_increment_i { 0x302 } else { 0x300 };
+        assert_eq!(
+            state.index, expected_index,
+            "load_store_increment_i={load_store_increment_i}"
+        );
+    }
+}
+
+#[test]
+fn dxyn_honors_draw_wrap_quirk() {
+    let source = "
+        LD I, sprite
+        LD V0, 60
+        LD V1, 0
+        DRW V0, V1, 1
+    sprite:
+        DB 0xFF
+    ";
+
+    for draw_wrap in [false, true] {
+        let quirks = Chip8Quirks {
+            draw_wrap,
+            ..MODERN_QUIRKS
+        };
+        let state = run_program(source, quirks, 4);
+
+        let wrapped_pixel = state.screen_buffer[2];
+        if draw_wrap {
+            assert_eq!(wrapped_pixel, 1, "the last 4 columns should wrap to x=0..4");
+        } else {
+            assert_eq!(wrapped_pixel, 0, "columns past the right edge should be clipped, not wrapped");
+        }
+    }
+}
+
+/// `run_rom_until` never ticks timers, so under a `display_wait` profile
+/// (`ORIGINAL_QUIRKS`) it must clear `waiting_for_vblank` itself after each
+/// cycle -- otherwise the first `DRW` would wedge every remaining cycle
+/// into a silent no-op and `V0` below would never reach its final value.
+#[test]
+fn display_wait_profile_keeps_making_progress_past_the_first_draw() {
+    let source = "
+        LD I, sprite
+        LD V0, 0
+        LD V1, 0
+        DRW V0, V1, 1
+        DRW V0, V1, 1
+        LD V0, 0x42
+    sprite:
+        DB 0xFF
+    ";
+
+    let state = run_program(source, ORIGINAL_QUIRKS, 6);
+
+    assert_eq!(state.registers[0], 0x42, "execution should not stall after the first DRW");
+}
+
+/// Runs the same composite program under every combination of the four
+/// quirk flags, so a regression in `handle_family_8`'s `SHR` or the `BNNN`
+/// jump is caught regardless of which other quirks are active alongside it.
+/// The program has no `FX55`/`FX65` or `DRW`; those quirks are checked by
+/// the dedicated tests above.
+#[test]
+fn quirk_matrix_covers_every_combination_of_the_four_flags() {
+    let source = "
+        LD V0, 0x05
+        LD V1, 0x03
+        SHR V0, V1
+        LD V2, 0x10
+        LD V3, 0x01
+        JP V3, 0x45
+    ";
+
+    for shift_uses_vy in [false, true] {
+        for load_store_increment_i in [false, true] {
+            for jump_with_vx in [false, true] {
+                for draw_wrap in [false, true] {
+                    let quirks = Chip8Quirks {
+                        shift_uses_vy,
+                        load_store_increment_i,
+                        jump_with_vx,
+                        draw_wrap,
+                        ..MODERN_QUIRKS
+                    };
+                    let state = run_program(source, quirks, 6);
+
+                    let expected_shr = if shift_uses_vy { 0x03 >> 1 } else { 0x05 >> 1 };
+                    assert_eq!(state.registers[0], expected_shr);
+
+                    // `JP V3, 0x45` encodes nnn=0x345 (the x_reg is folded
+                    // into nnn's top nibble); the jump then adds either V3
+                    // (jump_with_vx) or V0 (classic BNNN, post-SHR above).
+                    let expected_pc = if jump_with_vx {
+                        0x345 + 0x01
+                    } else {
+                        0x345 + expected_shr as usize
+                    };
+                    assert_eq!(state.pc, expected_pc);
+                }
+            }
+        }
+    }
+}
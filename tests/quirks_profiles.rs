@@ -0,0 +1,18 @@
+use chip8_emulator_rs::{load_quirks_profile, MODERN_QUIRKS, ORIGINAL_QUIRKS, SCHIP_QUIRKS};
+
+#[test]
+fn load_quirks_profile_accepts_the_historical_platform_aliases() {
+    assert_eq!(load_quirks_profile("cosmac_vip").unwrap(), ORIGINAL_QUIRKS);
+    assert_eq!(load_quirks_profile("chip48").unwrap(), MODERN_QUIRKS);
+    assert_eq!(load_quirks_profile("superchip").unwrap(), SCHIP_QUIRKS);
+}
+
+#[test]
+fn load_quirks_profile_is_case_insensitive_for_aliases() {
+    assert_eq!(load_quirks_profile("COSMAC_VIP").unwrap(), ORIGINAL_QUIRKS);
+}
+
+#[test]
+fn load_quirks_profile_rejects_unknown_names() {
+    assert!(load_quirks_profile("nonexistent").is_err());
+}
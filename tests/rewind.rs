@@ -0,0 +1,60 @@
+use chip8_emulator_rs::{create_state, execute_opcode, RewindBuffer, MODERN_QUIRKS};
+
+#[test]
+fn snapshot_and_restore_round_trip_register_state() {
+    let mut state = create_state(None, Some(0x1234)).unwrap();
+    state.registers[4] = 0x99;
+    state.index = 0x456;
+
+    let bytes = state.snapshot();
+    let restored = chip8_emulator_rs::EmulatorState::restore(&bytes).unwrap();
+
+    assert_eq!(restored.registers, state.registers);
+    assert_eq!(restored.index, state.index);
+    assert_eq!(restored.rng_state, state.rng_state);
+}
+
+#[test]
+fn rewind_buffer_captures_every_interval_cycles() {
+    let state = create_state(None, None).unwrap();
+    let mut rewind = RewindBuffer::new(2, 10);
+
+    rewind.record_cycle(&state);
+    assert_eq!(rewind.len(), 0);
+    rewind.record_cycle(&state);
+    assert_eq!(rewind.len(), 1);
+}
+
+#[test]
+fn rewind_buffer_restores_a_prior_snapshot_and_drops_oldest_past_capacity() {
+    let mut state = create_state(None, None).unwrap();
+    let mut rewind = RewindBuffer::new(1, 2);
+
+    rewind.record_cycle(&state);
+    state.registers[0] = 1;
+    rewind.record_cycle(&state);
+    state.registers[0] = 2;
+    rewind.record_cycle(&state);
+    state.registers[0] = 3;
+
+    assert_eq!(rewind.len(), 2, "capacity should cap at 2 snapshots");
+
+    assert!(rewind.rewind(&mut state));
+    assert_eq!(state.registers[0], 2);
+
+    assert!(rewind.rewind(&mut state));
+    assert_eq!(state.registers[0], 1);
+
+    assert!(!rewind.rewind(&mut state), "buffer should now be empty");
+}
+
+#[test]
+fn rewind_buffer_does_not_capture_between_intervals() {
+    let mut state = create_state(None, None).unwrap();
+    let mut rewind = RewindBuffer::new(5, 10);
+
+    execute_opcode(&mut state, 0x6001, MODERN_QUIRKS).unwrap();
+    rewind.record_cycle(&state);
+
+    assert_eq!(rewind.len(), 0);
+}
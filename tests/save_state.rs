@@ -0,0 +1,46 @@
+use chip8_emulator_rs::{create_state, load_state, save_state, Chip8Error};
+
+#[test]
+fn save_and_load_round_trips_full_state() {
+    let mut state = create_state(None, Some(0xDEAD_BEEF)).unwrap();
+    state.registers[3] = 0x42;
+    state.index = 0x321;
+    state.pc = 0x250;
+    state.delay_timer = 7;
+    state.sound_timer = 9;
+    state.stack.push(0x300);
+    state.screen_buffer[5] = 1;
+    state.selected_planes = 0b11;
+    state.plane2_buffer[5] = 1;
+
+    let path = std::env::temp_dir().join("chip8_emulator_rs_save_round_trip.sav");
+    save_state(&state, &path).unwrap();
+    let loaded = load_state(&path).unwrap();
+    std::fs::remove_file(&path).ok();
+
+    assert_eq!(loaded.memory, state.memory);
+    assert_eq!(loaded.registers, state.registers);
+    assert_eq!(loaded.stack, state.stack);
+    assert_eq!(loaded.screen_buffer, state.screen_buffer);
+    assert_eq!(loaded.plane2_buffer, state.plane2_buffer);
+    assert_eq!(loaded.selected_planes, state.selected_planes);
+    assert_eq!(loaded.pc, state.pc);
+    assert_eq!(loaded.index, state.index);
+    assert_eq!(loaded.delay_timer, state.delay_timer);
+    assert_eq!(loaded.sound_timer, state.sound_timer);
+    assert_eq!(loaded.rng_state, state.rng_state);
+}
+
+#[test]
+fn load_state_rejects_a_file_with_no_magic_header() {
+    let path = std::env::temp_dir().join("chip8_emulator_rs_save_bad_magic.sav");
+    std::fs::write(&path, b"not a save file").unwrap();
+
+    let result = load_state(&path);
+    std::fs::remove_file(&path).ok();
+
+    assert!(matches!(
+        result,
+        Err(Chip8Error::UnsupportedSaveVersion { .. })
+    ));
+}